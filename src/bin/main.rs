@@ -1,4 +1,4 @@
-use linked_list::{LinkedList, LinkedIndexU16, Max, Min};
+use linked_list::{LinkedIndexU16, LinkedList, Min, PushError};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 struct S(pub u32);
@@ -21,7 +21,7 @@ fn main() {
     ll.push(S(6)).unwrap();
     ll.push(S(11)).unwrap();
 
-    if let Err(v) = ll.push(S(8)) {
+    if let Err(PushError::Full(v)) = ll.push(S(8)) {
         println!("Rejected {}", v.0);
     } else {
         panic!("Sould have been rejected")
@@ -42,14 +42,14 @@ fn main() {
 
     println!("List: {:?}", ll);
 
-    println!("");
+    println!();
 
     println!("Iter");
     for v in ll.iter() {
         println!("v: {}", v.0);
     }
 
-    println!("");
+    println!();
     println!("poping 1005");
 
     ll.find_mut(|v| v.0 == 1005).unwrap().pop();
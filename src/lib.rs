@@ -1,69 +1,697 @@
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+use core::cmp::Ordering;
 use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{self, Chain, Once};
 use core::marker::PhantomData;
-use core::mem::MaybeUninit;
+use core::mem::{self, MaybeUninit};
 use core::ops::{Deref, DerefMut};
 use core::ptr;
 
-pub trait LinkedListIndex: Copy {
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "mpsc")]
+mod mpsc;
+mod pool;
+#[cfg(feature = "critical-section")]
+mod shared;
+mod skip;
+#[cfg(test)]
+mod test_util;
+#[cfg(feature = "alloc")]
+mod vec;
+
+#[cfg(feature = "mpsc")]
+pub use mpsc::{LinkedListMpsc, MpscConsumer};
+pub use pool::{LinkedListPool, PoolFindMut};
+#[cfg(feature = "critical-section")]
+pub use shared::SharedLinkedList;
+#[cfg(feature = "skip-list")]
+pub use skip::skip_lanes;
+#[cfg(feature = "alloc")]
+pub use vec::LinkedListVec;
+
+/// Private supertrait that keeps [`LinkedListIndex`] from being implemented outside the crate.
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// The index type of the nodes in a list, implemented for [`LinkedIndexU8`], [`LinkedIndexU16`],
+/// [`LinkedIndexU32`] and [`LinkedIndexUsize`].
+///
+/// # Safety
+///
+/// This trait is sealed. The lists index their nodes unchecked based on it, so an
+/// implementation must uphold:
+///
+/// - `NONE` is the only value that `option` maps to `None`.
+/// - Every index below `MAX_LEN` is representable: `new_unchecked(i).option()` is `Some(i)`
+///   and `get_unchecked` gives back `i`.
+///
+/// ```compile_fail,E0277
+/// use linked_list::LinkedListIndex;
+///
+/// #[derive(Clone, Copy)]
+/// struct MyIndex(u8);
+///
+/// unsafe impl LinkedListIndex for MyIndex {}
+/// ```
+pub unsafe trait LinkedListIndex: Copy + sealed::Sealed {
+    /// The index marking the end of the list.
+    #[doc(hidden)]
+    const NONE: Self;
+    /// The largest capacity the index can address, all indexes must be below `NONE`.
+    #[doc(hidden)]
+    const MAX_LEN: usize;
     #[doc(hidden)]
     unsafe fn new_unchecked(val: usize) -> Self;
     #[doc(hidden)]
     unsafe fn get_unchecked(self) -> usize;
     #[doc(hidden)]
     fn option(self) -> Option<usize>;
-    #[doc(hidden)]
-    fn none() -> Self;
 }
 
 /// Marker for Min sorted [`LinkedList`].
+///
+/// Equal elements are placed before the ones already in the list, so they are popped in reverse
+/// insertion order. Use [`MinStable`] for insertion order.
+///
+/// Elements are compared with `PartialOrd`. An element that can not be compared with the one it
+/// is checked against, like `f32::NAN`, is inserted before it, so where incomparable elements end
+/// up depends on the order they were pushed in. Wrap the kind in [`IncomparableLast`],
+/// [`RejectIncomparable`] or [`Total`] to choose a policy for them.
 pub struct Min;
 
 /// Marker for Max sorted [`LinkedList`].
+///
+/// Equal elements are placed before the ones already in the list, so they are popped in reverse
+/// insertion order. Use [`MaxStable`] for insertion order.
+///
+/// Incomparable elements are handled as for [`Min`].
 pub struct Max;
 
+/// Marker for a [`LinkedList`] sorted as `Kind`, where equal elements are placed after the ones
+/// already in the list so they are popped in insertion order (FIFO).
+pub struct Stable<Kind>(PhantomData<Kind>);
+
+/// Marker for Min sorted [`LinkedList`], equal elements are popped in insertion order.
+pub type MinStable = Stable<Min>;
+
+/// Marker for Max sorted [`LinkedList`], equal elements are popped in insertion order.
+pub type MaxStable = Stable<Max>;
+
+/// Marker for a [`LinkedList`] sorted as `Kind`, where elements that can not be compared with
+/// themselves, like `f32::NAN`, are sorted after all other elements.
+///
+/// Incomparable elements are kept in insertion order among themselves if `Kind` is [`Stable`],
+/// or the kind is wrapped in it.
+///
+/// # Example
+///
+/// ```
+/// use linked_list::{IncomparableLast, LinkedList, Min};
+/// let mut ll: LinkedList<f32, _, IncomparableLast<Min>, 4> = LinkedList::new_usize();
+///
+/// for v in [2.0, f32::NAN, 1.0, 3.0] {
+///     ll.push(v).unwrap();
+/// }
+///
+/// assert_eq!(ll.pop(), Ok(1.0));
+/// assert_eq!(ll.pop(), Ok(2.0));
+/// assert_eq!(ll.pop(), Ok(3.0));
+/// assert!(ll.pop().unwrap().is_nan());
+/// ```
+pub struct IncomparableLast<Kind>(PhantomData<Kind>);
+
+/// Marker for a [`LinkedList`] sorted as `Kind`, where elements that can not be compared with
+/// themselves, like `f32::NAN`, are rejected by [`LinkedList::push`] with
+/// [`PushError::Incomparable`].
+///
+/// Elements that become incomparable while they are in the list, through
+/// [`LinkedList::update`] or [`LinkedList::find_mut`], are sorted last as for
+/// [`IncomparableLast`], as are elements pushed with [`LinkedList::push_unchecked`].
+///
+/// # Example
+///
+/// ```
+/// use linked_list::{LinkedList, Max, PushError, RejectIncomparable};
+/// let mut ll: LinkedList<f64, _, RejectIncomparable<Max>, 4> = LinkedList::new_usize();
+///
+/// ll.push(1.0).unwrap();
+/// assert!(matches!(ll.push(f64::NAN), Err(PushError::Incomparable(v)) if v.is_nan()));
+/// assert_eq!(ll.len(), 1);
+/// ```
+pub struct RejectIncomparable<Kind>(PhantomData<Kind>);
+
+/// Marker for a [`LinkedList`] sorted as [`Min`] or [`Max`] with `Ord`, so elements that are
+/// only `PartialOrd`, like `f32`, can not be stored in it.
+///
+/// ```compile_fail
+/// use linked_list::{LinkedList, Min, Total};
+/// let mut ll: LinkedList<f32, _, Total<Min>, 4> = LinkedList::new_usize();
+///
+/// ll.push(1.0).unwrap();
+/// ```
+///
+/// # Example
+///
+/// ```
+/// use linked_list::{LinkedList, Max, Total};
+/// let mut ll: LinkedList<u32, _, Total<Max>, 4> = LinkedList::new_usize();
+///
+/// ll.push(1).unwrap();
+/// ll.push(2).unwrap();
+/// assert_eq!(ll.peek(), Some(&2));
+/// ```
+pub struct Total<Kind>(PhantomData<Kind>);
+
+/// Compares two elements, used to sort a [`LinkedList`] through the [`By`] marker.
+pub trait Compare<T> {
+    /// Compares `a` to `b`, elements are sorted in ascending order so `Ordering::Less` places `a`
+    /// before `b`.
+    fn compare(a: &T, b: &T) -> Ordering;
+}
+
+/// Marker for a [`LinkedList`] sorted in ascending order by the comparator `C`.
+///
+/// Equal elements are placed before the ones already in the list, wrap it in [`Stable`] for
+/// insertion order. Elements do not need to implement `PartialOrd`.
+///
+/// # Example
+///
+/// ```
+/// use core::cmp::Ordering;
+/// use linked_list::{By, Compare, LinkedList};
+///
+/// struct Task {
+///     deadline: u32,
+///     id: u8,
+/// }
+///
+/// struct ByDeadline;
+///
+/// impl Compare<Task> for ByDeadline {
+///     fn compare(a: &Task, b: &Task) -> Ordering {
+///         a.deadline.cmp(&b.deadline)
+///     }
+/// }
+///
+/// let mut ll: LinkedList<Task, _, By<ByDeadline>, 3> = LinkedList::new_usize();
+///
+/// ll.push(Task { deadline: 20, id: 0 }).ok().unwrap();
+/// ll.push(Task { deadline: 10, id: 1 }).ok().unwrap();
+/// ll.push(Task { deadline: 30, id: 2 }).ok().unwrap();
+///
+/// let ids: Vec<_> = ll.iter().map(|task| task.id).collect();
+/// assert_eq!(ids, [1, 0, 2]);
+/// ```
+pub struct By<C>(PhantomData<C>);
+
 /// Sealed traits and implementations for [`LinkedList`].
 mod kind {
-    use super::{Max, Min};
+    use super::{By, Compare, IncomparableLast, Max, Min, RejectIncomparable, Stable, Total};
     use core::cmp::Ordering;
 
-    /// The linked list kind: minimum first or maximum first.
-    pub unsafe trait Kind {
+    /// The linked list kind: how elements are compared and sorted.
+    ///
+    /// # Safety
+    ///
+    /// This trait is sealed, it is only implemented for [`Min`], [`Max`], [`By`], [`Stable`],
+    /// [`IncomparableLast`], [`RejectIncomparable`] and [`Total`].
+    pub unsafe trait Kind<T> {
+        /// The ordering of an element compared to an element after it.
         #[doc(hidden)]
         fn ordering() -> Option<Ordering>;
+
+        /// Compares two elements.
+        #[doc(hidden)]
+        fn compare(a: &T, b: &T) -> Option<Ordering>;
+
+        /// If equal elements are kept in insertion order.
+        #[doc(hidden)]
+        const STABLE: bool = false;
+
+        /// Checks if `a` sorts strictly before `b`.
+        #[doc(hidden)]
+        #[inline]
+        fn is_before(a: &T, b: &T) -> bool {
+            Self::compare(a, b) == Self::ordering()
+        }
+
+        /// Checks if an element in the list stays before a new element.
+        #[doc(hidden)]
+        #[inline]
+        fn stays_before(existing: &T, new: &T) -> bool {
+            let ordering = Self::compare(existing, new);
+
+            ordering == Self::ordering() || (Self::STABLE && ordering == Some(Ordering::Equal))
+        }
+
+        /// Checks if an element may be pushed to the list.
+        #[doc(hidden)]
+        #[inline]
+        fn accepts(_value: &T) -> bool {
+            true
+        }
+    }
+
+    unsafe impl<T: PartialOrd> Kind<T> for Min {
+        #[inline]
+        fn ordering() -> Option<Ordering> {
+            Some(Ordering::Less)
+        }
+
+        #[inline]
+        fn compare(a: &T, b: &T) -> Option<Ordering> {
+            a.partial_cmp(b)
+        }
+    }
+
+    unsafe impl<T: PartialOrd> Kind<T> for Max {
+        #[inline]
+        fn ordering() -> Option<Ordering> {
+            Some(Ordering::Greater)
+        }
+
+        #[inline]
+        fn compare(a: &T, b: &T) -> Option<Ordering> {
+            a.partial_cmp(b)
+        }
+    }
+
+    unsafe impl<T, C: Compare<T>> Kind<T> for By<C> {
+        #[inline]
+        fn ordering() -> Option<Ordering> {
+            Some(Ordering::Less)
+        }
+
+        #[inline]
+        fn compare(a: &T, b: &T) -> Option<Ordering> {
+            Some(C::compare(a, b))
+        }
+    }
+
+    unsafe impl<T, K: Kind<T>> Kind<T> for Stable<K> {
+        #[inline]
+        fn ordering() -> Option<Ordering> {
+            K::ordering()
+        }
+
+        #[inline]
+        fn compare(a: &T, b: &T) -> Option<Ordering> {
+            K::compare(a, b)
+        }
+
+        const STABLE: bool = true;
+
+        #[inline]
+        fn accepts(value: &T) -> bool {
+            K::accepts(value)
+        }
+    }
+
+    unsafe impl<T, K: Kind<T>> Kind<T> for IncomparableLast<K> {
+        #[inline]
+        fn ordering() -> Option<Ordering> {
+            K::ordering()
+        }
+
+        #[inline]
+        fn compare(a: &T, b: &T) -> Option<Ordering> {
+            // Only incomparable pairs pay for the extra comparisons
+            K::compare(a, b).or_else(|| {
+                match (K::compare(a, a).is_some(), K::compare(b, b).is_some()) {
+                    (true, false) => K::ordering(),
+                    (false, true) => K::ordering().map(Ordering::reverse),
+                    _ => Some(Ordering::Equal),
+                }
+            })
+        }
+
+        const STABLE: bool = K::STABLE;
+
+        #[inline]
+        fn accepts(value: &T) -> bool {
+            K::accepts(value)
+        }
+    }
+
+    unsafe impl<T, K: Kind<T>> Kind<T> for RejectIncomparable<K> {
+        #[inline]
+        fn ordering() -> Option<Ordering> {
+            K::ordering()
+        }
+
+        #[inline]
+        fn compare(a: &T, b: &T) -> Option<Ordering> {
+            IncomparableLast::<K>::compare(a, b)
+        }
+
+        const STABLE: bool = K::STABLE;
+
+        #[inline]
+        fn accepts(value: &T) -> bool {
+            K::accepts(value) && K::compare(value, value).is_some()
+        }
     }
 
-    unsafe impl Kind for Min {
+    unsafe impl<T: Ord> Kind<T> for Total<Min> {
         #[inline]
         fn ordering() -> Option<Ordering> {
             Some(Ordering::Less)
         }
+
+        #[inline]
+        fn compare(a: &T, b: &T) -> Option<Ordering> {
+            Some(a.cmp(b))
+        }
     }
 
-    unsafe impl Kind for Max {
+    unsafe impl<T: Ord> Kind<T> for Total<Max> {
         #[inline]
         fn ordering() -> Option<Ordering> {
             Some(Ordering::Greater)
         }
+
+        #[inline]
+        fn compare(a: &T, b: &T) -> Option<Ordering> {
+            Some(a.cmp(b))
+        }
+    }
+}
+
+/// Sealed trait for the node storage of a [`LinkedListInner`].
+mod storage {
+    use super::Node;
+
+    /// The nodes of a list.
+    ///
+    /// # Safety
+    ///
+    /// This trait is sealed, the nodes returned must be the same between calls except for nodes
+    /// added by `grow`.
+    pub unsafe trait Storage<T, Idx, const LANES: usize> {
+        #[doc(hidden)]
+        fn nodes(&self) -> &[Node<T, Idx, LANES>];
+
+        #[doc(hidden)]
+        fn nodes_mut(&mut self) -> &mut [Node<T, Idx, LANES>];
+
+        /// Checks if `grow` would add a node.
+        #[doc(hidden)]
+        #[inline]
+        fn can_grow(&self) -> bool {
+            false
+        }
+
+        /// Adds a free node at the end, returns `false` if the storage cannot grow.
+        #[doc(hidden)]
+        #[inline]
+        fn grow(&mut self) -> bool {
+            false
+        }
+    }
+
+    unsafe impl<T, Idx, const LANES: usize> Storage<T, Idx, LANES> for [Node<T, Idx, LANES>] {
+        #[inline(always)]
+        fn nodes(&self) -> &[Node<T, Idx, LANES>] {
+            self
+        }
+
+        #[inline(always)]
+        fn nodes_mut(&mut self) -> &mut [Node<T, Idx, LANES>] {
+            self
+        }
+    }
+
+    unsafe impl<T, Idx, const N: usize, const LANES: usize> Storage<T, Idx, LANES>
+        for [Node<T, Idx, LANES>; N]
+    {
+        #[inline(always)]
+        fn nodes(&self) -> &[Node<T, Idx, LANES>] {
+            self
+        }
+
+        #[inline(always)]
+        fn nodes_mut(&mut self) -> &mut [Node<T, Idx, LANES>] {
+            self
+        }
     }
 }
 
-/// A node in the linked list.
-pub struct Node<T, Idx> {
+/// A node in the doubly linked list, with the next node in each of `LANES` express lanes.
+pub struct Node<T, Idx, const LANES: usize = 0> {
     val: MaybeUninit<T>,
     next: Idx,
+    prev: Idx,
+    /// Odd while the node holds a value, even while it is free.
+    generation: u32,
+    lanes: [Idx; LANES],
 }
 
-/// The linked list.
-pub struct LinkedList<T, Idx, Kind, const N: usize>
+/// A handle to an element in a [`LinkedList`] or [`LinkedListPool`], returned from
+/// [`LinkedList::push`] and [`LinkedListPool::push`].
+///
+/// The handle stays valid while the element is in the list, also when it is resorted through
+/// [`LinkedList::update`] or [`LinkedList::find_mut`]. Once the element is removed the handle is
+/// stale, and the checked operations return [`StaleHandle`] even if the node has been reused by
+/// a newer element.
+///
+/// Each node counts its generation in a wrapping `u32`, so a handle is only mistaken for a new
+/// element if it is kept while its node is reused `2^31` times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Handle<Idx> {
+    index: Idx,
+    generation: u32,
+}
+
+/// Error returned when a [`Handle`] no longer points to an element in the list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaleHandle;
+
+impl fmt::Display for StaleHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("handle does not point to an element in the list")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StaleHandle {}
+
+/// Error returned when popping from an empty list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PopError;
+
+impl fmt::Display for PopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the list is empty")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PopError {}
+
+/// Error returned when an element could not be pushed to a list, gives back the element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushError<T> {
+    /// The list is full.
+    Full(T),
+    /// The element can not be compared with itself, returned by lists sorted with
+    /// [`RejectIncomparable`].
+    Incomparable(T),
+}
+
+impl<T> PushError<T> {
+    /// Get back the element that could not be pushed.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        match self {
+            PushError::Full(value) | PushError::Incomparable(value) => value,
+        }
+    }
+}
+
+impl<T> fmt::Display for PushError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PushError::Full(_) => "the list is full",
+            PushError::Incomparable(_) => "the element can not be compared with itself",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> std::error::Error for PushError<T> {}
+
+/// The elements that could not be pushed by [`LinkedList::try_from_iter`]: the rejected element
+/// followed by the rest of the iterator.
+pub type Overflow<T, I> = Chain<Once<T>, I>;
+
+/// The storage independent part of the linked list, use [`LinkedList`] or [`LinkedListView`].
+///
+/// The nodes are stored in the last field `list`, which is an array in a [`LinkedList`] and a
+/// slice in a [`LinkedListView`]. `LANES` is the number of express lanes over the list, which is
+/// zero except for a `SkipLinkedList` of the `skip-list` feature.
+///
+/// # Thread safety
+///
+/// A list is [`Send`] if the elements are, and [`Sync`] if the elements are, whatever the kind.
+/// The guards borrowing a list, like [`FindMut`], follow from the list: they can be sent to
+/// another thread if the elements are `Send`, which resorts the element there when the guard is
+/// dropped, and shared if the elements are `Sync`.
+///
+/// ```compile_fail
+/// use linked_list::{LinkedIndexU8, LinkedList, Min};
+/// use std::rc::Rc;
+///
+/// fn assert_send<T: Send>(_: &T) {}
+///
+/// let ll: LinkedList<Rc<u32>, LinkedIndexU8, Min, 4> = LinkedList::new();
+/// assert_send(&ll);
+/// ```
+pub struct LinkedListInner<T, Idx, Kind, S: ?Sized, const LANES: usize = 0>
+where
+    Idx: LinkedListIndex,
+{
+    links: Links<Idx, LANES>,
+    free: FreeList<Idx>,
+    _marker: PhantomData<(T, Kind)>,
+    list: S,
+}
+
+// Safety: The list owns its elements and only refers to nodes by index, so there is no shared
+// state left behind when it is moved to another thread. `Kind` is a marker that is never
+// instantiated, the elements are only compared through its associated functions.
+unsafe impl<T, Idx, Kind, S, const LANES: usize> Send for LinkedListInner<T, Idx, Kind, S, LANES>
+where
+    T: Send,
+    Idx: LinkedListIndex + Send,
+    S: ?Sized + Send,
+{
+}
+
+// Safety: A shared list only hands out shared references to its elements, and has no interior
+// mutability.
+unsafe impl<T, Idx, Kind, S, const LANES: usize> Sync for LinkedListInner<T, Idx, Kind, S, LANES>
+where
+    T: Sync,
+    Idx: LinkedListIndex + Sync,
+    S: ?Sized + Sync,
+{
+}
+
+/// The ends of a sorted chain of nodes, and the first node in each express lane.
+struct Links<Idx, const LANES: usize = 0> {
+    head: Idx,
+    tail: Idx,
+    len: usize,
+    lanes: [Idx; LANES],
+}
+
+/// Where a node is linked into a chain, found by `Links::search`.
+struct Position<Idx, const LANES: usize = 0> {
+    prev: Idx,
+    /// The node to link after in each express lane.
+    lanes: [Idx; LANES],
+}
+
+impl<Idx, const LANES: usize> Position<Idx, LANES>
 where
     Idx: LinkedListIndex,
 {
-    list: [Node<T, Idx>; N],
+    /// The position at the end of an empty chain.
+    const START: Self = Position {
+        prev: Idx::NONE,
+        lanes: [Idx::NONE; LANES],
+    };
+
+    /// Moves the position past the node at `new`, after it was linked at the end of the chain.
+    ///
+    /// # Safety
+    ///
+    /// `new` must be a valid index for `Idx`.
+    #[inline]
+    unsafe fn advance(&mut self, new: usize) {
+        self.prev = Idx::new_unchecked(new);
+        skip::advance_lanes(&mut self.lanes, new);
+    }
+}
+
+/// The nodes of an array that are not linked into any list.
+struct FreeList<Idx> {
+    /// Released nodes, chained through `next`.
     head: Idx,
-    free: Idx,
-    _kind: PhantomData<Kind>,
+    /// Nodes from `fresh` and up have never been used.
+    fresh: usize,
 }
 
+/// The linked list.
+pub type LinkedList<T, Idx, Kind, const N: usize> =
+    LinkedListInner<T, Idx, Kind, [Node<T, Idx>; N]>;
+
+/// A linked list with the capacity erased, which lets functions take lists of any capacity
+/// without being generic over `N`.
+///
+/// A [`LinkedList`] dereferences to its view. Calling the operations through the view compiles
+/// them once for all capacities instead of once per `N`.
+///
+/// # Example
+///
+/// ```
+/// use linked_list::{LinkedIndexU8, LinkedList, LinkedListView, Min};
+///
+/// fn push_all(ll: &mut LinkedListView<u32, LinkedIndexU8, Min>, values: &[u32]) {
+///     for &value in values {
+///         ll.push(value).unwrap();
+///     }
+/// }
+///
+/// let mut small: LinkedList<u32, LinkedIndexU8, Min, 4> = LinkedList::new_u8();
+/// let mut large: LinkedList<u32, LinkedIndexU8, Min, 16> = LinkedList::new_u8();
+///
+/// push_all(&mut small, &[3, 1, 2]);
+/// push_all(large.as_mut_view(), &[5, 4]);
+///
+/// assert!(small.iter().eq(&[1, 2, 3]));
+/// assert!(large.iter().eq(&[4, 5]));
+/// ```
+pub type LinkedListView<T, Idx, Kind> = LinkedListInner<T, Idx, Kind, [Node<T, Idx>]>;
+
+/// A linked list with `LANES` express lanes over it, for expected `O(log N)` sorted insertion
+/// and search. Use [`skip_lanes`] for the number of lanes that fits the capacity `N`.
+///
+/// Every lane costs one index in every node, so a list with `LinkedIndexU16` and 3 lanes spends
+/// about 6 more bytes per node than a [`LinkedList`]. Lists without lanes are not affected by the
+/// `skip-list` feature.
+///
+/// Removing an element walks back through the list to the previous node in each of its lanes,
+/// which is expected `O(1)` but up to `O(N)` for the few elements in the top lane.
+///
+/// # Example
+///
+/// ```
+/// use linked_list::{skip_lanes, LinkedIndexU16, Min, SkipLinkedList};
+///
+/// let mut ll: SkipLinkedList<u32, LinkedIndexU16, Min, 1000, { skip_lanes(1000) }> =
+///     SkipLinkedList::new_u16();
+///
+/// for value in (0..1000).rev() {
+///     ll.push(value).unwrap();
+/// }
+///
+/// assert_eq!(ll.peek(), Some(&0));
+/// ```
+#[cfg(feature = "skip-list")]
+pub type SkipLinkedList<T, Idx, Kind, const N: usize, const LANES: usize> =
+    LinkedListInner<T, Idx, Kind, [Node<T, Idx, LANES>; N], LANES>;
+
+/// A [`SkipLinkedList`] with the capacity erased, see [`LinkedListView`].
+#[cfg(feature = "skip-list")]
+pub type SkipLinkedListView<T, Idx, Kind, const LANES: usize> =
+    LinkedListInner<T, Idx, Kind, [Node<T, Idx, LANES>], LANES>;
+
 macro_rules! impl_index_and_const_new {
     ($name:ident, $ty:ty, $new_name:ident, $max_val:literal) => {
         #[doc = concat!(
@@ -72,11 +700,23 @@ macro_rules! impl_index_and_const_new {
         #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
         pub struct $name($ty);
 
-        impl LinkedListIndex for $name {
+        impl sealed::Sealed for $name {}
+
+        // Safety: `NONE` is the maximum value and `MAX_LEN` is at most that, so every index below
+        // `MAX_LEN` fits and is not `NONE`.
+        unsafe impl LinkedListIndex for $name {
+            const NONE: Self = $name(<$ty>::MAX);
+
+            const MAX_LEN: usize = if <$ty>::MAX as u128 > usize::MAX as u128 {
+                usize::MAX
+            } else {
+                <$ty>::MAX as usize
+            };
+
             #[doc = concat!("Safety: `val` is <= `", stringify!($max_val), "`.")]
             #[inline(always)]
             unsafe fn new_unchecked(val: usize) -> Self {
-                Self::new_unchecked(val as $ty)
+                $name(val as $ty)
             }
 
             /// This is only valid if `self.option()` is not `None`.
@@ -93,171 +733,874 @@ macro_rules! impl_index_and_const_new {
                     Some(self.0 as usize)
                 }
             }
+        }
 
-            #[inline(always)]
-            fn none() -> Self {
-                Self::none()
+        impl<T, Kind, const N: usize, const LANES: usize>
+            LinkedListInner<T, $name, Kind, [Node<T, $name, LANES>; N], LANES>
+        {
+            /// Create a new linked list, same as [`LinkedList::new`].
+            pub const fn $new_name() -> Self {
+                Self::new()
             }
         }
+    }
+}
 
-        impl $name {
-            /// Needed for a `const fn new()`.
-            #[inline]
-            const unsafe fn new_unchecked(value: $ty) -> Self {
-                $name(value)
-            }
+impl_index_and_const_new!(LinkedIndexU8, u8, new_u8, 254); // val is 2^8 - 2 (one less than max)
+impl_index_and_const_new!(LinkedIndexU16, u16, new_u16, 65_534); // val is 2^16 - 2
+impl_index_and_const_new!(LinkedIndexU32, u32, new_u32, 4_294_967_294); // val is 2^32 - 2
+impl_index_and_const_new!(LinkedIndexUsize, usize, new_usize, 4_294_967_294); // val is 2^32 - 2
 
-            /// Needed for a `const fn new()`.
-            #[inline]
-            const fn none() -> Self {
-                $name(<$ty>::MAX)
-            }
-        }
+/// The type of a [`LinkedList`] using the smallest index type that can address `N` elements.
+///
+/// The index type is picked at compile time, so `N` must be a constant expression that does not
+/// depend on generic parameters, e.g. a literal or a `const` item. The type can be used as any
+/// other [`LinkedList`], and is constructed with [`LinkedList::new`].
+///
+/// # Example
+///
+/// ```
+/// use core::mem::size_of;
+/// use linked_list::{auto_linked_list, LinkedIndexU16, LinkedList, Min};
+///
+/// const CAPACITY: usize = 1000;
+///
+/// static mut QUEUE: auto_linked_list!(u32, Min, CAPACITY) = LinkedList::new();
+///
+/// // 1000 elements need a 16 bit index
+/// assert_eq!(
+///     size_of::<auto_linked_list!(u32, Min, CAPACITY)>(),
+///     size_of::<LinkedList<u32, LinkedIndexU16, Min, CAPACITY>>(),
+/// );
+/// ```
+#[macro_export]
+macro_rules! auto_linked_list {
+    ($t:ty, $kind:ty, $n:expr) => {
+        $crate::LinkedList<
+            $t,
+            <$crate::IndexFor<{ $crate::index_bits($n) }> as $crate::SelectIndex>::Idx,
+            $kind,
+            { $n },
+        >
+    };
+}
 
-        impl<T, Kind, const N: usize> LinkedList<T, $name, Kind, N> {
-            const UNINIT: Node<T, $name> = Node {
-                val: MaybeUninit::uninit(),
-                next: $name::none(),
-            };
+/// Index type selection for [`auto_linked_list!`], by the number of bits of the index.
+#[doc(hidden)]
+pub struct IndexFor<const BITS: u32>;
 
-            /// Create a new linked list.
-            pub const fn $new_name() -> Self {
-                let mut list = LinkedList {
-                    list: [Self::UNINIT; N],
-                    head: $name::none(),
-                    free: unsafe { $name::new_unchecked(0) },
-                    _kind: PhantomData,
-                };
+/// Index type selection for [`auto_linked_list!`].
+#[doc(hidden)]
+pub trait SelectIndex {
+    type Idx: LinkedListIndex;
+}
 
-                if N == 0 {
-                    list.free = $name::none();
-                    return list;
-                }
+impl SelectIndex for IndexFor<8> {
+    type Idx = LinkedIndexU8;
+}
+
+impl SelectIndex for IndexFor<16> {
+    type Idx = LinkedIndexU16;
+}
 
-                let mut free = 0;
+impl SelectIndex for IndexFor<32> {
+    type Idx = LinkedIndexU32;
+}
 
-                // Initialize indexes
-                while free < N - 1 {
-                    list.list[free].next = unsafe { $name::new_unchecked(free as $ty + 1) };
-                    free += 1;
-                }
+impl SelectIndex for IndexFor<64> {
+    type Idx = LinkedIndexUsize;
+}
 
-                list
-            }
-        }
+/// The number of bits of the smallest index type that can address `n` elements.
+#[doc(hidden)]
+// The `LinkedIndexU32` limit is `usize::MAX` where `usize` is 32 bits wide
+#[allow(clippy::absurd_extreme_comparisons)]
+pub const fn index_bits(n: usize) -> u32 {
+    if n <= LinkedIndexU8::MAX_LEN {
+        8
+    } else if n <= LinkedIndexU16::MAX_LEN {
+        16
+    } else if n <= LinkedIndexU32::MAX_LEN {
+        32
+    } else {
+        64
     }
 }
 
-impl_index_and_const_new!(LinkedIndexU8, u8, new_u8, 254); // val is 2^8 - 2 (one less than max)
-impl_index_and_const_new!(LinkedIndexU16, u16, new_u16, 65_534); // val is 2^16 - 2
-impl_index_and_const_new!(LinkedIndexUsize, usize, new_usize, 4_294_967_294); // val is 2^32 - 2
-
-impl<T, Idx, Kind, const N: usize> LinkedList<T, Idx, Kind, N>
+impl<T, Idx, Kind, const N: usize, const LANES: usize>
+    LinkedListInner<T, Idx, Kind, [Node<T, Idx, LANES>; N], LANES>
 where
     Idx: LinkedListIndex,
 {
-    /// Internal access helper
-    #[inline(always)]
-    fn node_at(&self, index: usize) -> &Node<T, Idx> {
-        // Safety: The entire `self.list` is initialized in `new`, which makes this safe.
-        unsafe { self.list.get_unchecked(index) }
+    /// The indexes `0..N` must be below `Idx::NONE`, which marks the end of the list.
+    const CAPACITY_CHECK: () = assert!(N <= Idx::MAX_LEN, "`N` is too large for the index type");
+
+    /// Create a new linked list.
+    ///
+    /// `N` can be at most the maximum value of the index type, larger capacities fail to
+    /// compile.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedIndexU8, LinkedList, Max};
+    /// static mut LL: LinkedList<u32, LinkedIndexU8, Max, 255> = LinkedList::new();
+    /// ```
+    ///
+    /// ```compile_fail,E0080
+    /// use linked_list::{LinkedIndexU8, LinkedList, Max};
+    /// let ll: LinkedList<u32, LinkedIndexU8, Max, 256> = LinkedList::new();
+    /// ```
+    ///
+    /// ```compile_fail,E0080
+    /// use linked_list::{LinkedIndexU16, LinkedList, Max};
+    /// let ll: LinkedList<u32, LinkedIndexU16, Max, 65_536> = LinkedList::new();
+    /// ```
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY_CHECK;
+
+        // Nodes are taken in order from `fresh` until they are released into the free queue, so
+        // the nodes do not need to be linked up front
+        LinkedListInner {
+            links: Links::EMPTY,
+            free: FreeList::EMPTY,
+            _marker: PhantomData,
+            list: [Node::UNINIT; N],
+        }
     }
 
-    /// Internal access helper
-    #[inline(always)]
-    fn node_at_mut(&mut self, index: usize) -> &mut Node<T, Idx> {
-        // Safety: The entire `self.list` is initialized in `new`, which makes this safe.
-        unsafe { self.list.get_unchecked_mut(index) }
+    /// Get a reference to the list with the capacity erased.
+    #[inline]
+    pub fn as_view(&self) -> &LinkedListInner<T, Idx, Kind, [Node<T, Idx, LANES>], LANES> {
+        self
     }
 
-    /// Internal access helper
-    #[inline(always)]
-    fn write_data_in_node_at(&mut self, index: usize, data: T) {
-        // Safety: The entire `self.list` is initialized in `new`, which makes this safe.
-        unsafe {
-            self.node_at_mut(index).val.as_mut_ptr().write(data);
-        }
+    /// Get a mutable reference to the list with the capacity erased.
+    #[inline]
+    pub fn as_mut_view(
+        &mut self,
+    ) -> &mut LinkedListInner<T, Idx, Kind, [Node<T, Idx, LANES>], LANES> {
+        self
+    }
+}
+
+impl<T, Idx, Kind, const N: usize, const LANES: usize>
+    LinkedListInner<T, Idx, Kind, [Node<T, Idx, LANES>; N], LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+{
+    /// Create a new linked list from the elements of an iterator.
+    ///
+    /// If an element can not be pushed, the list is returned together with the overflow: the
+    /// rejected element followed by the rest of the iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedIndexU8, LinkedList, Min};
+    /// type List = LinkedList<u32, LinkedIndexU8, Min, 3>;
+    ///
+    /// let ll = List::try_from_iter([3, 1, 2]).unwrap();
+    /// assert!(ll.iter().eq(&[1, 2, 3]));
+    ///
+    /// let (ll, overflow) = List::try_from_iter([3, 1, 2, 5, 4]).unwrap_err();
+    /// assert!(ll.iter().eq(&[1, 2, 3]));
+    /// assert!(overflow.eq([5, 4]));
+    /// ```
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, (Self, Overflow<T, I::IntoIter>)>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut list = Self::new();
+
+        match list.push_iter(iter.into_iter()) {
+            Ok(()) => Ok(list),
+            Err(overflow) => Err((list, overflow)),
+        }
+    }
+}
+
+impl<T, Idx, Kind, const N: usize, const LANES: usize> Deref
+    for LinkedListInner<T, Idx, Kind, [Node<T, Idx, LANES>; N], LANES>
+where
+    Idx: LinkedListIndex,
+{
+    type Target = LinkedListInner<T, Idx, Kind, [Node<T, Idx, LANES>], LANES>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self
+    }
+}
+
+impl<T, Idx, Kind, const N: usize, const LANES: usize> DerefMut
+    for LinkedListInner<T, Idx, Kind, [Node<T, Idx, LANES>; N], LANES>
+where
+    Idx: LinkedListIndex,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self
+    }
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> LinkedListInner<T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    /// Internal access helper
+    #[inline(always)]
+    fn node_at(&self, index: usize) -> &Node<T, Idx, LANES> {
+        // Safety: The entire `self.list` is initialized in `new`, which makes this safe.
+        unsafe { self.list.nodes().get_unchecked(index) }
+    }
+
+    /// Internal access helper
+    #[inline(always)]
+    fn node_at_mut(&mut self, index: usize) -> &mut Node<T, Idx, LANES> {
+        // Safety: The entire `self.list` is initialized in `new`, which makes this safe.
+        unsafe { self.list.nodes_mut().get_unchecked_mut(index) }
+    }
+
+    /// Internal access helper
+    #[inline(always)]
+    fn read_data_in_node_at(&self, index: usize) -> &T {
+        // Safety: The entire `self.list` is initialized in `new`, which makes this safe.
+        unsafe { &*self.node_at(index).val.as_ptr() }
+    }
+
+    /// Internal access helper
+    #[inline(always)]
+    fn read_mut_data_in_node_at(&mut self, index: usize) -> &mut T {
+        // Safety: The entire `self.list` is initialized in `new`, which makes this safe.
+        unsafe { &mut *self.node_at_mut(index).val.as_mut_ptr() }
+    }
+}
+
+impl<T, Idx, const LANES: usize> Node<T, Idx, LANES>
+where
+    Idx: LinkedListIndex,
+{
+    const UNINIT: Self = Node {
+        val: MaybeUninit::uninit(),
+        next: Idx::NONE,
+        prev: Idx::NONE,
+        generation: 0,
+        lanes: [Idx::NONE; LANES],
+    };
+
+    /// Internal access helper
+    ///
+    /// # Safety
+    ///
+    /// The node must hold a value.
+    #[inline(always)]
+    unsafe fn value(&self) -> &T {
+        &*self.val.as_ptr()
+    }
+
+    /// Internal access helper
+    ///
+    /// # Safety
+    ///
+    /// The node must hold a value.
+    #[inline(always)]
+    unsafe fn value_mut(&mut self) -> &mut T {
+        &mut *self.val.as_mut_ptr()
+    }
+}
+
+impl<Idx, const LANES: usize> Links<Idx, LANES>
+where
+    Idx: LinkedListIndex,
+{
+    const EMPTY: Self = Links {
+        head: Idx::NONE,
+        tail: Idx::NONE,
+        len: 0,
+        lanes: [Idx::NONE; LANES],
+    };
+
+    /// Finds the sorted position of `value` in the chain.
+    ///
+    /// This is the only place where values are compared when linking, and the chain is not
+    /// changed, so a panicking comparison leaves the chain as it was.
+    ///
+    /// # Safety
+    ///
+    /// All nodes in this chain must be in `nodes`.
+    unsafe fn search<Kind, T>(
+        &self,
+        nodes: &[Node<T, Idx, LANES>],
+        value: &T,
+    ) -> Position<Idx, LANES>
+    where
+        Kind: kind::Kind<T>,
+    {
+        // Start the search from the express lanes if there are any
+        let lanes = self.search_lanes(nodes, |existing| Kind::stays_before(existing, value));
+        let mut prev = lanes.first().copied().unwrap_or(Idx::NONE);
+
+        let mut next = match prev.option() {
+            Some(prev) => nodes.get_unchecked(prev).next,
+            None => self.head,
+        };
+
+        // Search the list for the correct placement
+        while let Some(current) = next.option() {
+            if !Kind::stays_before(nodes.get_unchecked(current).value(), value) {
+                break;
+            }
+
+            prev = next;
+            next = nodes.get_unchecked(current).next;
+        }
+
+        Position { prev, lanes }
+    }
+
+    /// Links the node at `new` into the position found by `search`.
+    ///
+    /// # Safety
+    ///
+    /// The node at `new` must hold a value and must not be linked into any list or free list,
+    /// and the chain must not have changed since the position was found, except for unlinking
+    /// `new`.
+    unsafe fn link_at<T>(
+        &mut self,
+        nodes: &mut [Node<T, Idx, LANES>],
+        new: usize,
+        at: &Position<Idx, LANES>,
+    ) {
+        let prev = at.prev;
+        let next = match prev.option() {
+            Some(prev) => nodes.get_unchecked(prev).next,
+            None => self.head,
+        };
+
+        nodes.get_unchecked_mut(new).next = next;
+        nodes.get_unchecked_mut(new).prev = prev;
+
+        match prev.option() {
+            Some(prev) => nodes.get_unchecked_mut(prev).next = Idx::new_unchecked(new),
+            None => self.head = Idx::new_unchecked(new),
+        }
+
+        match next.option() {
+            Some(next) => nodes.get_unchecked_mut(next).prev = Idx::new_unchecked(new),
+            None => self.tail = Idx::new_unchecked(new),
+        }
+
+        self.link_lanes(nodes, new, &at.lanes);
+
+        self.len += 1;
+    }
+
+    /// Unlinks the node at `index` from the chain, the value is left in the node.
+    ///
+    /// Complexity is worst-case `O(1)` without express lanes.
+    ///
+    /// # Safety
+    ///
+    /// The node at `index` must be linked into this chain, and all nodes in the chain must be in
+    /// `nodes`.
+    unsafe fn unlink<T>(&mut self, nodes: &mut [Node<T, Idx, LANES>], index: usize) {
+        // The lanes are unlinked first, as they find their previous nodes through the list
+        self.unlink_lanes(nodes, index);
+
+        let next = nodes.get_unchecked(index).next;
+        let prev = nodes.get_unchecked(index).prev;
+
+        if let Some(prev) = prev.option() {
+            nodes.get_unchecked_mut(prev).next = next;
+        } else {
+            self.head = next;
+        }
+
+        if let Some(next) = next.option() {
+            nodes.get_unchecked_mut(next).prev = prev;
+        } else {
+            self.tail = prev;
+        }
+
+        self.len -= 1;
+    }
+}
+
+impl<Idx> FreeList<Idx>
+where
+    Idx: LinkedListIndex,
+{
+    const EMPTY: Self = FreeList {
+        head: Idx::NONE,
+        fresh: 0,
+    };
+
+    /// Takes a free node and stores `value` in it, returns the index and the new generation of
+    /// the node.
+    ///
+    /// # Safety
+    ///
+    /// There must be a free node left in `nodes`.
+    unsafe fn occupy<T, const LANES: usize>(
+        &mut self,
+        nodes: &mut [Node<T, Idx, LANES>],
+        value: T,
+    ) -> (usize, u32) {
+        // Take the next free spot and store the data
+        let index = match self.head.option() {
+            Some(free) => {
+                self.head = nodes.get_unchecked(free).next;
+                free
+            }
+            None => {
+                self.fresh += 1;
+                self.fresh - 1
+            }
+        };
+
+        let node = nodes.get_unchecked_mut(index);
+        node.val.as_mut_ptr().write(value);
+        node.generation = node.generation.wrapping_add(1);
+
+        (index, node.generation)
+    }
+
+    /// Releases an unlinked node at `index` into the free list and extracts its value.
+    ///
+    /// # Safety
+    ///
+    /// The node at `index` must hold a value and must not be linked into any list.
+    unsafe fn release<T, const LANES: usize>(
+        &mut self,
+        nodes: &mut [Node<T, Idx, LANES>],
+        index: usize,
+    ) -> T {
+        let node = nodes.get_unchecked_mut(index);
+        node.generation = node.generation.wrapping_add(1);
+        node.next = self.head;
+        self.head = Idx::new_unchecked(index);
+
+        node.val.as_ptr().read()
+    }
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> LinkedListInner<T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    /// Clones the elements into the empty list `target`, in order and into its first nodes.
+    ///
+    /// The elements are appended without comparing them, and each one is linked before the next
+    /// is cloned, so a panicking `clone` leaves `target` with the elements cloned so far.
+    ///
+    /// # Safety
+    ///
+    /// `target` must be empty and have never held an element, and must have room for all
+    /// elements.
+    unsafe fn clone_compact_into<S2>(&self, target: &mut LinkedListInner<T, Idx, Kind, S2, LANES>)
+    where
+        T: Clone,
+        S2: ?Sized + storage::Storage<T, Idx, LANES>,
+    {
+        let mut position = Position::START;
+
+        for value in self.iter() {
+            let value = value.clone();
+            let nodes = target.list.nodes_mut();
+            let (index, _) = target.free.occupy(nodes, value);
+
+            target.links.link_at(nodes, index, &position);
+            position.advance(index);
+        }
+    }
+
+    /// Pushes the elements of `iter` until one is rejected, which is returned in front of the
+    /// rest of the elements.
+    fn push_iter<I>(&mut self, mut iter: I) -> Result<(), Overflow<T, I>>
+    where
+        I: Iterator<Item = T>,
+    {
+        while let Some(value) = iter.next() {
+            if let Err(err) = self.push(value) {
+                return Err(iter::once(err.into_inner()).chain(iter));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finds the sorted position of `value` in the list, see `Links::search`.
+    #[inline]
+    fn search(&self, value: &T) -> Position<Idx, LANES> {
+        // Safety: All nodes linked into the list are in `self.list`.
+        unsafe { self.links.search::<Kind, _>(self.list.nodes(), value) }
+    }
+
+    /// Links the node at `new` into the position found by `search`.
+    ///
+    /// # Safety
+    ///
+    /// The node at `new` must hold a value and must not be linked into the list or free list,
+    /// and the list must not have changed since the position was found, except for unlinking
+    /// `new`.
+    #[inline]
+    unsafe fn link_at(&mut self, new: usize, at: &Position<Idx, LANES>) {
+        self.links.link_at(self.list.nodes_mut(), new, at);
+    }
+
+    /// Unlinks the node at `index` from the list, the value is left in the node.
+    ///
+    /// # Safety
+    ///
+    /// The node at `index` must be linked into the list.
+    #[inline]
+    unsafe fn unlink(&mut self, index: usize) {
+        self.links.unlink(self.list.nodes_mut(), index);
+    }
+
+    /// Releases an unlinked node at `index` into the free list and extracts its value.
+    ///
+    /// # Safety
+    ///
+    /// The node at `index` must hold a value and must have been unlinked from the list.
+    #[inline]
+    unsafe fn release(&mut self, index: usize) -> T {
+        self.free.release(self.list.nodes_mut(), index)
+    }
+
+    /// Pushes a value onto the list without checking if the list is full.
+    ///
+    /// Incomparable elements are not rejected for [`RejectIncomparable`] lists, they are sorted
+    /// last instead.
+    ///
+    /// Complexity is worst-case `O(N)`, and expected `O(log N)` with the express lanes of a
+    /// `SkipLinkedList`.
+    ///
+    /// # Safety
+    ///
+    /// The list must not be full, see [`is_full`](LinkedListInner::is_full). For a
+    /// `LinkedListVec` this means that either a node is free or the list can still grow, the
+    /// list grows by one node if all nodes are taken.
+    pub unsafe fn push_unchecked(&mut self, value: T) -> Handle<Idx> {
+        // Find the position before taking a node, so a panicking comparison leaves the list as it
+        // was and only drops the value
+        let position = self.search(&value);
+        if self.links.len == self.list.nodes().len() {
+            // Only storage that can grow gets here, the caller checked that the list is not full
+            let grown = self.list.grow();
+            debug_assert!(grown);
+        }
+        let (new, generation) = self.free.occupy(self.list.nodes_mut(), value);
+        self.link_at(new, &position);
+
+        Handle {
+            index: Idx::new_unchecked(new),
+            generation,
+        }
+    }
+
+    /// Checks that a handle points to an element in this list and returns its index.
+    #[inline]
+    fn check_handle(&self, handle: Handle<Idx>) -> Result<usize, StaleHandle> {
+        match handle.index.option() {
+            // Handles are only created for occupied nodes, so a matching (odd) generation means
+            // that the node still holds the same element
+            Some(index)
+                if index < self.list.nodes().len()
+                    && self.node_at(index).generation == handle.generation =>
+            {
+                Ok(index)
+            }
+            _ => Err(StaleHandle),
+        }
+    }
+
+    /// Pushes an element to the linked list and sorts it into place.
+    ///
+    /// Fails with [`PushError::Full`] if the list is full, or with [`PushError::Incomparable`] if
+    /// the list is sorted with [`RejectIncomparable`] and the element can not be compared with
+    /// itself.
+    ///
+    /// If a comparison panics, the element is dropped and the list is left unchanged.
+    ///
+    /// Complexity is worst-case `O(N)`, and expected `O(log N)` with the express lanes of a
+    /// `SkipLinkedList`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max, PushError};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// // The largest value will always be first
+    /// ll.push(1).unwrap();
+    /// assert_eq!(ll.peek(), Some(&1));
+    ///
+    /// ll.push(2).unwrap();
+    /// assert_eq!(ll.peek(), Some(&2));
+    ///
+    /// ll.push(3).unwrap();
+    /// assert_eq!(ll.peek(), Some(&3));
+    ///
+    /// // This will not fit in the queue.
+    /// assert_eq!(ll.push(4), Err(PushError::Full(4)));
+    /// ```
+    pub fn push(&mut self, value: T) -> Result<Handle<Idx>, PushError<T>> {
+        if !Kind::accepts(&value) {
+            Err(PushError::Incomparable(value))
+        } else if !self.is_full() {
+            Ok(unsafe { self.push_unchecked(value) })
+        } else {
+            Err(PushError::Full(value))
+        }
+    }
+
+    /// Pushes an element to the linked list and sorts it into place, if the list is full the
+    /// last element is evicted to make room.
+    ///
+    /// The element is only inserted into a full list if it sorts strictly before the last
+    /// element, so equal elements already in the list are kept for all kinds. Returns the evicted
+    /// element, or gives back the value if it was rejected. Handles to an evicted element become
+    /// stale.
+    ///
+    /// If a comparison panics, the element is dropped, as is the last element if it was already
+    /// evicted.
+    ///
+    /// Complexity is worst-case `O(N)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max, PushError};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// // Keep the 3 largest values
+    /// assert_eq!(ll.push_evict(1), Ok(None));
+    /// assert_eq!(ll.push_evict(5), Ok(None));
+    /// assert_eq!(ll.push_evict(3), Ok(None));
+    ///
+    /// // The smallest value is evicted
+    /// assert_eq!(ll.push_evict(4), Ok(Some(1)));
+    ///
+    /// // Values not better than the smallest value are rejected
+    /// assert_eq!(ll.push_evict(3), Err(PushError::Full(3)));
+    /// assert_eq!(ll.push_evict(2), Err(PushError::Full(2)));
+    ///
+    /// assert_eq!(ll.pop(), Ok(5));
+    /// assert_eq!(ll.pop(), Ok(4));
+    /// assert_eq!(ll.pop(), Ok(3));
+    /// ```
+    pub fn push_evict(&mut self, value: T) -> Result<Option<T>, PushError<T>> {
+        if !Kind::accepts(&value) {
+            return Err(PushError::Incomparable(value));
+        }
+
+        if !self.is_full() {
+            unsafe { self.push_unchecked(value) };
+            return Ok(None);
+        }
+
+        match self.peek_back() {
+            Some(tail) if Kind::is_before(&value, tail) => {
+                let evicted = unsafe { self.pop_back_unchecked() };
+                unsafe { self.push_unchecked(value) };
+
+                Ok(Some(evicted))
+            }
+            _ => Err(PushError::Full(value)),
+        }
+    }
+
+    /// Checks if the element a handle points to is still in the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// let handle = ll.push(1).unwrap();
+    /// assert!(ll.contains(handle));
+    ///
+    /// ll.pop().unwrap();
+    /// assert!(!ll.contains(handle));
+    /// ```
+    #[inline]
+    pub fn contains(&self, handle: Handle<Idx>) -> bool {
+        self.check_handle(handle).is_ok()
+    }
+
+    /// Get a reference to the element a handle points to.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max, StaleHandle};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// let handle = ll.push(1).unwrap();
+    /// ll.push(2).unwrap();
+    ///
+    /// assert_eq!(ll.get(handle), Ok(&1));
+    ///
+    /// // The node is reused by the new element, but the handle is detected as stale
+    /// ll.remove(handle).unwrap();
+    /// ll.push(3).unwrap();
+    ///
+    /// assert_eq!(ll.get(handle), Err(StaleHandle));
+    /// ```
+    pub fn get(&self, handle: Handle<Idx>) -> Result<&T, StaleHandle> {
+        let index = self.check_handle(handle)?;
+
+        Ok(self.read_data_in_node_at(index))
+    }
+
+    /// Get a reference to the element a handle points to without checking the handle.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    ///
+    /// # Safety
+    ///
+    /// The handle must come from this list and its element must not have been removed.
+    pub unsafe fn get_unchecked(&self, handle: Handle<Idx>) -> &T {
+        self.read_data_in_node_at(handle.index.get_unchecked())
+    }
+
+    /// Get the element a handle points to so it can be changed and resorted, without searching
+    /// for it.
+    ///
+    /// Complexity is worst-case `O(1)`, resorting is worst-case `O(N)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// let handle = ll.push(1).unwrap();
+    /// ll.push(2).unwrap();
+    /// ll.push(3).unwrap();
+    ///
+    /// // Update the value, the handle stays valid after the resort
+    /// let mut update = ll.update(handle).unwrap();
+    /// *update += 1000;
+    /// update.finish();
+    ///
+    /// assert_eq!(ll.peek(), Some(&1001));
+    /// assert_eq!(ll.get(handle), Ok(&1001));
+    /// ```
+    pub fn update(
+        &mut self,
+        handle: Handle<Idx>,
+    ) -> Result<FindMut<'_, T, Idx, Kind, S, LANES>, StaleHandle> {
+        self.check_handle(handle)?;
+
+        Ok(FindMut {
+            index: handle.index,
+            list: self,
+            maybe_changed: false,
+        })
     }
 
-    /// Internal access helper
-    #[inline(always)]
-    fn read_data_in_node_at(&self, index: usize) -> &T {
-        // Safety: The entire `self.list` is initialized in `new`, which makes this safe.
-        unsafe { &*self.node_at(index).val.as_ptr() }
+    /// Get the element a handle points to so it can be changed and resorted, without checking
+    /// the handle.
+    ///
+    /// Complexity is worst-case `O(1)`, resorting is worst-case `O(N)`.
+    ///
+    /// # Safety
+    ///
+    /// The handle must come from this list and its element must not have been removed.
+    pub unsafe fn update_unchecked(
+        &mut self,
+        handle: Handle<Idx>,
+    ) -> FindMut<'_, T, Idx, Kind, S, LANES> {
+        FindMut {
+            index: handle.index,
+            list: self,
+            maybe_changed: false,
+        }
     }
 
-    /// Internal access helper
-    #[inline(always)]
-    fn read_mut_data_in_node_at(&mut self, index: usize) -> &mut T {
-        // Safety: The entire `self.list` is initialized in `new`, which makes this safe.
-        unsafe { &mut *self.node_at_mut(index).val.as_mut_ptr() }
-    }
+    /// Remove the element a handle points to from the list, without searching for it.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max, PopError, StaleHandle};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// ll.push(1).unwrap();
+    /// let handle = ll.push(2).unwrap();
+    /// ll.push(3).unwrap();
+    ///
+    /// assert_eq!(ll.remove(handle), Ok(2));
+    /// assert_eq!(ll.remove(handle), Err(StaleHandle));
+    ///
+    /// assert_eq!(ll.pop(), Ok(3));
+    /// assert_eq!(ll.pop(), Ok(1));
+    /// assert_eq!(ll.pop(), Err(PopError));
+    /// ```
+    pub fn remove(&mut self, handle: Handle<Idx>) -> Result<T, StaleHandle> {
+        let index = self.check_handle(handle)?;
 
-    /// Internal access helper
-    #[inline(always)]
-    fn extract_data_in_node_at(&mut self, index: usize) -> T {
-        // Safety: The entire `self.list` is initialized in `new`, which makes this safe.
-        unsafe { self.node_at(index).val.as_ptr().read() }
+        Ok(unsafe {
+            self.unlink(index);
+            self.release(index)
+        })
     }
-}
 
-impl<T, Idx, Kind, const N: usize> LinkedList<T, Idx, Kind, N>
-where
-    T: PartialOrd,
-    Idx: LinkedListIndex,
-    Kind: kind::Kind,
-{
-    /// Pushes a value onto the list without checking if the list is full.
+    /// Remove the element a handle points to from the list, without checking the handle.
     ///
-    /// Complexity is worst-case `O(N)`.
+    /// Complexity is worst-case `O(1)`.
     ///
     /// # Safety
     ///
-    /// Assumes that the list is not full.
-    pub unsafe fn push_unchecked(&mut self, value: T) {
-        let new = self.free.get_unchecked();
+    /// The handle must come from this list and its element must not have been removed.
+    pub unsafe fn remove_unchecked(&mut self, handle: Handle<Idx>) -> T {
+        let index = handle.index.get_unchecked();
 
-        // Store the data and update the next free spot
-        self.write_data_in_node_at(new, value);
-        self.free = self.node_at(new).next;
-
-        if let Some(head) = self.head.option() {
-            // Check if we need to replace head
-            if self
-                .read_data_in_node_at(head as usize)
-                .partial_cmp(self.read_data_in_node_at(new as usize))
-                != Kind::ordering()
-            {
-                self.node_at_mut(new as usize).next = self.head;
-                self.head = Idx::new_unchecked(new);
-            } else {
-                // It's not head, search the list for the correct placement
-                let mut current = head;
-
-                while let Some(next) = self.node_at(current).next.option() {
-                    if self
-                        .read_data_in_node_at(next as usize)
-                        .partial_cmp(self.read_data_in_node_at(new as usize))
-                        != Kind::ordering()
-                    {
-                        break;
-                    }
-
-                    current = next;
-                }
+        self.unlink(index);
+        self.release(index)
+    }
 
-                self.node_at_mut(new).next = self.node_at(current).next;
-                self.node_at_mut(current).next = Idx::new_unchecked(new);
-            }
-        } else {
-            self.node_at_mut(new).next = self.head;
-            self.head = Idx::new_unchecked(new);
+    /// Get an iterator over the sorted list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// ll.push(1).unwrap();
+    /// ll.push(2).unwrap();
+    ///
+    /// let mut iter = ll.iter();
+    ///
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T, Idx, Kind, LANES> {
+        Iter {
+            nodes: self.list.nodes(),
+            index: self.links.head,
+            _kind: PhantomData,
         }
     }
 
-    /// Pushes an element to the linked list and sorts it into place.
+    /// Get an iterator that removes and yields all elements in sorted order.
     ///
-    /// Complexity is worst-case `O(N)`.
+    /// If the iterator is dropped early the remaining elements are removed and dropped, so the
+    /// list is always empty afterwards.
     ///
     /// # Example
     ///
@@ -265,28 +1608,25 @@ where
     /// use linked_list::{LinkedList, Max};
     /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
     ///
-    /// // The largest value will always be first
     /// ll.push(1).unwrap();
-    /// assert_eq!(ll.peek(), Some(&1));
-    ///
     /// ll.push(2).unwrap();
-    /// assert_eq!(ll.peek(), Some(&2));
-    ///
     /// ll.push(3).unwrap();
-    /// assert_eq!(ll.peek(), Some(&3));
     ///
-    /// // This will not fit in the queue.
-    /// assert_eq!(ll.push(4), Err(4));
+    /// let mut drain = ll.drain();
+    ///
+    /// assert_eq!(drain.next(), Some(3));
+    /// assert_eq!(drain.next_back(), Some(1));
+    /// drop(drain);
+    ///
+    /// assert!(ll.is_empty());
     /// ```
-    pub fn push(&mut self, value: T) -> Result<(), T> {
-        if !self.is_full() {
-            Ok(unsafe { self.push_unchecked(value) })
-        } else {
-            Err(value)
-        }
+    pub fn drain(&mut self) -> Drain<'_, T, Idx, Kind, S, LANES> {
+        Drain { list: self }
     }
 
-    /// Get an iterator over the sorted list.
+    /// Removes and drops all elements.
+    ///
+    /// Complexity is worst-case `O(N)`.
     ///
     /// # Example
     ///
@@ -297,16 +1637,85 @@ where
     /// ll.push(1).unwrap();
     /// ll.push(2).unwrap();
     ///
-    /// let mut iter = ll.iter();
+    /// ll.clear();
     ///
-    /// assert_eq!(iter.next(), Some(&2));
-    /// assert_eq!(iter.next(), Some(&1));
-    /// assert_eq!(iter.next(), None);
+    /// assert!(ll.is_empty());
     /// ```
-    pub fn iter(&self) -> Iter<'_, T, Idx, Kind, N> {
-        Iter {
+    pub fn clear(&mut self) {
+        self.drain().for_each(drop);
+    }
+
+    /// Retains only the elements for which `f` returns `true`, the others are removed and
+    /// dropped in a single pass.
+    ///
+    /// If `f` panics the elements visited so far are removed, the rest are kept.
+    ///
+    /// Complexity is worst-case `O(N)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max, PopError};
+    /// let mut ll: LinkedList<_, _, Max, 4> = LinkedList::new_usize();
+    ///
+    /// ll.push(1).unwrap();
+    /// ll.push(2).unwrap();
+    /// ll.push(3).unwrap();
+    /// ll.push(4).unwrap();
+    ///
+    /// // Only keep the odd values
+    /// ll.retain(|v| v % 2 == 1);
+    ///
+    /// assert_eq!(ll.pop(), Ok(3));
+    /// assert_eq!(ll.pop(), Ok(1));
+    /// assert_eq!(ll.pop(), Err(PopError));
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.extract_if(|v| !f(v)).for_each(drop);
+    }
+
+    /// Get an iterator that removes and yields the elements for which `f` returns `true`, in
+    /// sorted order. The other elements are kept.
+    ///
+    /// The list is only traversed as far as the iterator is consumed, if it is dropped early the
+    /// remaining elements are kept. If `f` panics the list is left with the elements removed so
+    /// far.
+    ///
+    /// Complexity is worst-case `O(N)` for the full traversal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max, PopError};
+    /// let mut ll: LinkedList<_, _, Max, 4> = LinkedList::new_usize();
+    ///
+    /// ll.push(1).unwrap();
+    /// ll.push(2).unwrap();
+    /// ll.push(3).unwrap();
+    /// ll.push(4).unwrap();
+    ///
+    /// let mut even = ll.extract_if(|v| v % 2 == 0);
+    ///
+    /// assert_eq!(even.next(), Some(4));
+    /// assert_eq!(even.next(), Some(2));
+    /// assert_eq!(even.next(), None);
+    /// drop(even);
+    ///
+    /// assert_eq!(ll.pop(), Ok(3));
+    /// assert_eq!(ll.pop(), Ok(1));
+    /// assert_eq!(ll.pop(), Err(PopError));
+    /// ```
+    pub fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, T, Idx, Kind, F, S, LANES>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        ExtractIf {
+            index: self.links.head,
             list: self,
-            index: self.head,
+            f,
         }
     }
 
@@ -315,7 +1724,7 @@ where
     /// # Example
     ///
     /// ```
-    /// use linked_list::{LinkedList, Max};
+    /// use linked_list::{LinkedList, Max, PopError};
     /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
     ///
     /// ll.push(1).unwrap();
@@ -330,39 +1739,83 @@ where
     /// assert_eq!(ll.pop(), Ok(1002));
     /// assert_eq!(ll.pop(), Ok(3));
     /// assert_eq!(ll.pop(), Ok(1));
-    /// assert_eq!(ll.pop(), Err(()));
+    /// assert_eq!(ll.pop(), Err(PopError));
     /// ```
-    pub fn find_mut<F>(&mut self, mut f: F) -> Option<FindMut<'_, T, Idx, Kind, N>>
+    pub fn find_mut<F>(&mut self, mut f: F) -> Option<FindMut<'_, T, Idx, Kind, S, LANES>>
     where
         F: FnMut(&T) -> bool,
     {
-        let head = self.head.option()?;
-
-        // Special-case, first element
-        if f(self.read_data_in_node_at(head as usize)) {
-            return Some(FindMut {
-                is_head: true,
-                prev_index: Idx::none(),
-                index: self.head,
-                list: self,
-                maybe_changed: false,
-            });
-        }
-
-        let mut current = head;
+        let mut index = self.links.head;
 
-        while let Some(next) = self.node_at(current as usize).next.option() {
-            if f(self.read_data_in_node_at(next as usize)) {
+        while let Some(current) = index.option() {
+            if f(self.read_data_in_node_at(current)) {
                 return Some(FindMut {
-                    is_head: false,
-                    prev_index: unsafe { Idx::new_unchecked(current) },
-                    index: unsafe { Idx::new_unchecked(next) },
+                    index,
                     list: self,
                     maybe_changed: false,
                 });
             }
 
-            current = next;
+            index = self.node_at(current).next;
+        }
+
+        None
+    }
+
+    /// Find the first element in the list that is equal to `value`, that can be changed and
+    /// resorted.
+    ///
+    /// Unlike [`LinkedList::find_mut`] this uses the ordering of the list, so the search stops at
+    /// the sorted position of `value`. Complexity is worst-case `O(N)`, and expected `O(log N)`
+    /// with the express lanes of a `SkipLinkedList`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max, PopError};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// ll.push(1).unwrap();
+    /// ll.push(2).unwrap();
+    /// ll.push(3).unwrap();
+    ///
+    /// // Find a value and update it
+    /// let mut find = ll.find_mut_ordered(&2).unwrap();
+    /// *find += 1000;
+    /// find.finish();
+    ///
+    /// assert!(ll.find_mut_ordered(&2).is_none());
+    ///
+    /// assert_eq!(ll.pop(), Ok(1002));
+    /// assert_eq!(ll.pop(), Ok(3));
+    /// assert_eq!(ll.pop(), Ok(1));
+    /// assert_eq!(ll.pop(), Err(PopError));
+    /// ```
+    pub fn find_mut_ordered(&mut self, value: &T) -> Option<FindMut<'_, T, Idx, Kind, S, LANES>> {
+        // Safety: All nodes linked into the list hold a value.
+        let prevs = unsafe {
+            self.links.search_lanes(self.list.nodes(), |existing| {
+                Kind::is_before(existing, value)
+            })
+        };
+        let mut index = match prevs.first().and_then(|prev| prev.option()) {
+            Some(prev) => self.node_at(prev).next,
+            None => self.links.head,
+        };
+
+        while let Some(current) = index.option() {
+            match Kind::compare(self.read_data_in_node_at(current), value) {
+                Some(Ordering::Equal) => {
+                    return Some(FindMut {
+                        index,
+                        list: self,
+                        maybe_changed: false,
+                    })
+                }
+                ordering if ordering == Kind::ordering() => index = self.node_at(current).next,
+                // Passed the position where `value` would be
+                _ => return None,
+            }
         }
 
         None
@@ -395,27 +1848,166 @@ where
     /// assert_eq!(ll_min.peek(), Some(&1));
     /// ```
     pub fn peek(&self) -> Option<&T> {
-        self.head
+        self.links
+            .head
             .option()
             .map(|head| self.read_data_in_node_at(head))
     }
 
+    /// Get the first element so it can be changed and resorted.
+    ///
+    /// Same as [`LinkedList::find_mut`] matching the first element, without calling a
+    /// predicate. The list is only resorted if the element has been accessed mutably.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max, PopError};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// assert!(ll.peek_mut().is_none());
+    ///
+    /// ll.push(1).unwrap();
+    /// ll.push(2).unwrap();
+    /// ll.push(3).unwrap();
+    ///
+    /// // Update the first value, it is resorted when the guard is dropped
+    /// *ll.peek_mut().unwrap() = 0;
+    /// assert_eq!(ll.peek(), Some(&2));
+    ///
+    /// // Or pop it
+    /// let peek = ll.peek_mut().unwrap();
+    /// assert_eq!(peek.pop(), 2);
+    ///
+    /// assert_eq!(ll.pop(), Ok(1));
+    /// assert_eq!(ll.pop(), Ok(0));
+    /// assert_eq!(ll.pop(), Err(PopError));
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, Idx, Kind, S, LANES>> {
+        self.links.head.option()?;
+
+        Some(PeekMut(FindMut {
+            index: self.links.head,
+            list: self,
+            maybe_changed: false,
+        }))
+    }
+
     /// Pop an element from the list without checking so the list is not empty.
     ///
     /// # Safety
     ///
     /// Assumes that the list is not empty.
     pub unsafe fn pop_unchecked(&mut self) -> T {
-        let head = self.head.get_unchecked();
-        let current = head;
-        self.head = self.node_at(head).next;
-        self.node_at_mut(current).next = self.free;
-        self.free = Idx::new_unchecked(current);
+        let head = self.links.head.get_unchecked();
+
+        self.unlink(head);
+        self.release(head)
+    }
+
+    /// Pops the first element in the list.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max, PopError};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// ll.push(1).unwrap();
+    /// ll.push(2).unwrap();
+    ///
+    /// assert_eq!(ll.pop(), Ok(2));
+    /// assert_eq!(ll.pop(), Ok(1));
+    /// assert_eq!(ll.pop(), Err(PopError));
+    /// ```
+    pub fn pop(&mut self) -> Result<T, PopError> {
+        self.pop_first().ok_or(PopError)
+    }
+
+    /// Pops the first element in the list, returns `None` if the list is empty.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// ll.push(1).unwrap();
+    /// ll.push(2).unwrap();
+    ///
+    /// assert_eq!(ll.pop_first(), Some(2));
+    /// assert_eq!(ll.pop_first(), Some(1));
+    /// assert_eq!(ll.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<T> {
+        if !self.is_empty() {
+            Some(unsafe { self.pop_unchecked() })
+        } else {
+            None
+        }
+    }
+
+    /// Peek at the last element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// // The smallest value will always be last
+    /// ll.push(2).unwrap();
+    /// assert_eq!(ll.peek_back(), Some(&2));
+    /// ll.push(3).unwrap();
+    /// assert_eq!(ll.peek_back(), Some(&2));
+    /// ll.push(1).unwrap();
+    /// assert_eq!(ll.peek_back(), Some(&1));
+    /// ```
+    pub fn peek_back(&self) -> Option<&T> {
+        self.links
+            .tail
+            .option()
+            .map(|tail| self.read_data_in_node_at(tail))
+    }
+
+    /// Pop the last element from the list without checking so the list is not empty.
+    ///
+    /// # Safety
+    ///
+    /// Assumes that the list is not empty.
+    pub unsafe fn pop_back_unchecked(&mut self) -> T {
+        let tail = self.links.tail.get_unchecked();
+
+        self.unlink(tail);
+        self.release(tail)
+    }
 
-        self.extract_data_in_node_at(current)
+    /// Pops the last element in the list.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max, PopError};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// ll.push(1).unwrap();
+    /// ll.push(2).unwrap();
+    ///
+    /// assert_eq!(ll.pop_back(), Ok(1));
+    /// assert_eq!(ll.pop_back(), Ok(2));
+    /// assert_eq!(ll.pop_back(), Err(PopError));
+    /// ```
+    pub fn pop_back(&mut self) -> Result<T, PopError> {
+        self.pop_last().ok_or(PopError)
     }
 
-    /// Pops the first element in the list.
+    /// Pops the last element in the list, returns `None` if the list is empty.
     ///
     /// Complexity is worst-case `O(1)`.
     ///
@@ -428,19 +2020,64 @@ where
     /// ll.push(1).unwrap();
     /// ll.push(2).unwrap();
     ///
-    /// assert_eq!(ll.pop(), Ok(2));
-    /// assert_eq!(ll.pop(), Ok(1));
-    /// assert_eq!(ll.pop(), Err(()));
+    /// assert_eq!(ll.pop_last(), Some(1));
+    /// assert_eq!(ll.pop_last(), Some(2));
+    /// assert_eq!(ll.pop_last(), None);
     /// ```
-    pub fn pop(&mut self) -> Result<T, ()> {
+    pub fn pop_last(&mut self) -> Option<T> {
         if !self.is_empty() {
-            Ok(unsafe { self.pop_unchecked() })
+            Some(unsafe { self.pop_back_unchecked() })
         } else {
-            Err(())
+            None
         }
     }
 
-    /// Checks if the linked list is full.
+    /// Returns the number of elements in the list.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max};
+    /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// assert_eq!(ll.len(), 0);
+    ///
+    /// ll.push(1).unwrap();
+    /// ll.push(2).unwrap();
+    /// assert_eq!(ll.len(), 2);
+    ///
+    /// ll.pop().unwrap();
+    /// assert_eq!(ll.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.links.len
+    }
+
+    /// Returns the maximum number of elements the list can hold.
+    ///
+    /// For a `LinkedListVec` this is the number of nodes allocated so far, the list grows past its
+    /// capacity until it holds as many elements as the index type can address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedList, Max};
+    /// let ll: LinkedList<u32, _, Max, 3> = LinkedList::new_usize();
+    ///
+    /// assert_eq!(ll.capacity(), 3);
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.list.nodes().len()
+    }
+
+    /// Checks if the linked list is full, so that pushing fails with [`PushError::Full`].
+    ///
+    /// A `LinkedListVec` is only full once it can not grow any further, also when all of its
+    /// nodes are taken.
     ///
     /// # Example
     ///
@@ -459,7 +2096,7 @@ where
     /// ```
     #[inline]
     pub fn is_full(&self) -> bool {
-        self.free.option().is_none()
+        self.links.len == self.list.nodes().len() && !self.list.can_grow()
     }
 
     /// Checks if the linked list is empty.
@@ -477,76 +2114,260 @@ where
     /// ```
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.head.option().is_none()
+        self.links.head.option().is_none()
     }
 }
 
 /// Iterator for the linked list.
-pub struct Iter<'a, T, Idx, Kind, const N: usize>
+pub struct Iter<'a, T, Idx, Kind, const LANES: usize = 0>
 where
-    T: PartialOrd,
     Idx: LinkedListIndex,
-    Kind: kind::Kind,
+    Kind: kind::Kind<T>,
 {
-    list: &'a LinkedList<T, Idx, Kind, N>,
+    nodes: &'a [Node<T, Idx, LANES>],
     index: Idx,
+    /// Only a marker, so the iterator is `Send` and `Sync` whatever the kind.
+    _kind: PhantomData<fn() -> Kind>,
 }
 
-impl<'a, T, Idx, Kind, const N: usize> Iterator for Iter<'a, T, Idx, Kind, N>
+impl<'a, T, Idx, Kind, const LANES: usize> Iterator for Iter<'a, T, Idx, Kind, LANES>
 where
-    T: PartialOrd,
     Idx: LinkedListIndex,
-    Kind: kind::Kind,
+    Kind: kind::Kind<T>,
 {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
         let index = self.index.option()?;
 
-        let node = self.list.node_at(index);
+        // Safety: The iterator only visits nodes linked into a list, which all hold a value.
+        let node = unsafe { self.nodes.get_unchecked(index) };
         self.index = node.next;
 
-        Some(self.list.read_data_in_node_at(index))
+        Some(unsafe { node.value() })
+    }
+}
+
+impl<'a, T, Idx, Kind, S, const LANES: usize> IntoIterator
+    for &'a LinkedListInner<T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, Idx, Kind, LANES>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Owning iterator for the linked list, yields the elements in sorted order.
+///
+/// Elements that are not consumed are dropped with the iterator.
+///
+/// # Example
+///
+/// ```
+/// use linked_list::{LinkedList, Max};
+/// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
+///
+/// ll.push(1).unwrap();
+/// ll.push(2).unwrap();
+/// ll.push(3).unwrap();
+///
+/// let values: Vec<_> = ll.into_iter().collect();
+///
+/// assert_eq!(values, [3, 2, 1]);
+/// ```
+pub struct IntoIter<T, Idx, Kind, S, const LANES: usize = 0>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: storage::Storage<T, Idx, LANES>,
+{
+    list: LinkedListInner<T, Idx, Kind, S, LANES>,
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> IntoIterator for LinkedListInner<T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: storage::Storage<T, Idx, LANES>,
+{
+    type Item = T;
+    type IntoIter = IntoIter<T, Idx, Kind, S, LANES>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> Iterator for IntoIter<T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: storage::Storage<T, Idx, LANES>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_first()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len(), Some(self.list.len()))
+    }
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> DoubleEndedIterator for IntoIter<T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: storage::Storage<T, Idx, LANES>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_last()
+    }
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> ExactSizeIterator for IntoIter<T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: storage::Storage<T, Idx, LANES>,
+{
+}
+
+/// Comes from [`LinkedList::drain`].
+pub struct Drain<'a, T, Idx, Kind, S = [Node<T, Idx>], const LANES: usize = 0>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    list: &'a mut LinkedListInner<T, Idx, Kind, S, LANES>,
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> Iterator for Drain<'_, T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_first()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len(), Some(self.list.len()))
+    }
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> DoubleEndedIterator for Drain<'_, T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_last()
+    }
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> ExactSizeIterator for Drain<'_, T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> Drop for Drain<'_, T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    fn drop(&mut self) {
+        // Each element is removed before it is dropped, if a drop panics the rest are left in
+        // the list and dropped with it
+        self.for_each(drop);
     }
 }
 
-/// Comes from [`LinkedList::find_mut`].
-pub struct FindMut<'a, T, Idx, Kind, const N: usize>
+/// Comes from [`LinkedList::extract_if`].
+pub struct ExtractIf<'a, T, Idx, Kind, F, S = [Node<T, Idx>], const LANES: usize = 0>
 where
-    T: PartialOrd,
     Idx: LinkedListIndex,
-    Kind: kind::Kind,
+    Kind: kind::Kind<T>,
+    F: FnMut(&T) -> bool,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
 {
-    list: &'a mut LinkedList<T, Idx, Kind, N>,
-    is_head: bool,
-    prev_index: Idx,
+    list: &'a mut LinkedListInner<T, Idx, Kind, S, LANES>,
     index: Idx,
-    maybe_changed: bool,
+    f: F,
 }
 
-impl<'a, T, Idx, Kind, const N: usize> FindMut<'a, T, Idx, Kind, N>
+impl<T, Idx, Kind, F, S, const LANES: usize> Iterator for ExtractIf<'_, T, Idx, Kind, F, S, LANES>
 where
-    T: PartialOrd,
     Idx: LinkedListIndex,
-    Kind: kind::Kind,
+    Kind: kind::Kind<T>,
+    F: FnMut(&T) -> bool,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
 {
-    fn pop_internal(&mut self) -> T {
-        if self.is_head {
-            // If it is the head element, we can do a normal pop
-            unsafe { self.list.pop_unchecked() }
-        } else {
-            // Somewhere in the list
-            let prev = unsafe { self.prev_index.get_unchecked() };
-            let curr = unsafe { self.index.get_unchecked() };
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(index) = self.index.option() {
+            // Step forward first, so the list is consistent if `f` panics
+            self.index = self.list.node_at(index).next;
+
+            if (self.f)(self.list.read_data_in_node_at(index)) {
+                return Some(unsafe {
+                    self.list.unlink(index);
+                    self.list.release(index)
+                });
+            }
+        }
+
+        None
+    }
+}
 
-            // Re-point the previous index
-            self.list.node_at_mut(prev).next = self.list.node_at_mut(curr).next;
+/// Comes from [`LinkedList::find_mut`] and [`LinkedList::update`].
+///
+/// The element is resorted when this is dropped, if it was accessed mutably. If a comparison
+/// panics while resorting, the element is removed from the list and dropped.
+///
+/// The guard is [`Send`] if the elements are, in which case the element is resorted on the
+/// thread that drops it, and [`Sync`] if the elements are.
+pub struct FindMut<'a, T, Idx, Kind, S = [Node<T, Idx>], const LANES: usize = 0>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    list: &'a mut LinkedListInner<T, Idx, Kind, S, LANES>,
+    index: Idx,
+    maybe_changed: bool,
+}
 
-            // Release the index into the free queue
-            self.list.node_at_mut(curr).next = self.list.free;
-            self.list.free = self.index;
+impl<'a, T, Idx, Kind, S, const LANES: usize> FindMut<'a, T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    fn pop_internal(&mut self) -> T {
+        let index = unsafe { self.index.get_unchecked() };
 
-            self.list.extract_data_in_node_at(curr)
+        unsafe {
+            self.list.unlink(index);
+            self.list.release(index)
         }
     }
 
@@ -557,7 +2378,7 @@ where
     /// # Example
     ///
     /// ```
-    /// use linked_list::{LinkedList, Max};
+    /// use linked_list::{LinkedList, Max, PopError};
     /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
     ///
     /// ll.push(1).unwrap();
@@ -570,10 +2391,12 @@ where
     ///
     /// assert_eq!(ll.pop(), Ok(3));
     /// assert_eq!(ll.pop(), Ok(1));
-    /// assert_eq!(ll.pop(), Err(()));
+    /// assert_eq!(ll.pop(), Err(PopError));
     /// ```
     #[inline]
     pub fn pop(mut self) -> T {
+        // The element is gone, there is nothing to resort
+        self.maybe_changed = false;
         self.pop_internal()
     }
 
@@ -587,7 +2410,7 @@ where
     /// # Example
     ///
     /// ```
-    /// use linked_list::{LinkedList, Max};
+    /// use linked_list::{LinkedList, Max, PopError};
     /// let mut ll: LinkedList<_, _, Max, 3> = LinkedList::new_usize();
     ///
     /// ll.push(1).unwrap();
@@ -604,7 +2427,7 @@ where
     /// assert_eq!(ll.pop(), Ok(1002));
     /// assert_eq!(ll.pop(), Ok(3));
     /// assert_eq!(ll.pop(), Ok(1));
-    /// assert_eq!(ll.pop(), Err(()));
+    /// assert_eq!(ll.pop(), Err(PopError));
     /// ```
     #[inline]
     pub fn finish(self) {
@@ -612,26 +2435,64 @@ where
     }
 }
 
-impl<T, Idx, Kind, const N: usize> Drop for FindMut<'_, T, Idx, Kind, N>
+impl<T, Idx, Kind, S, const LANES: usize> Drop for FindMut<'_, T, Idx, Kind, S, LANES>
 where
-    T: PartialOrd,
     Idx: LinkedListIndex,
-    Kind: kind::Kind,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
 {
     fn drop(&mut self) {
-        // Only resort the list if the element has changed
+        // Only resort the list if the element has changed, the element keeps its node so
+        // handles to it stay valid
         if self.maybe_changed {
-            let val = self.pop_internal();
-            unsafe { self.list.push_unchecked(val) };
+            let index = unsafe { self.index.get_unchecked() };
+
+            unsafe {
+                self.list.unlink(index);
+
+                // If a comparison panics the position of the element is unknown, so it is removed
+                // from the list and dropped
+                let guard = ReleaseGuard {
+                    list: &mut *self.list,
+                    index,
+                };
+                let position = guard.list.search(guard.list.read_data_in_node_at(index));
+                mem::forget(guard);
+
+                self.list.link_at(index, &position);
+            }
         }
     }
 }
 
-impl<T, Idx, Kind, const N: usize> Deref for FindMut<'_, T, Idx, Kind, N>
+/// Releases an unlinked node when dropped.
+struct ReleaseGuard<'a, T, Idx, Kind, S, const LANES: usize>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    list: &'a mut LinkedListInner<T, Idx, Kind, S, LANES>,
+    index: usize,
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> Drop for ReleaseGuard<'_, T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    fn drop(&mut self) {
+        // Safety: The guard is only created for an unlinked node holding a value.
+        unsafe { drop(self.list.release(self.index)) }
+    }
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> Deref for FindMut<'_, T, Idx, Kind, S, LANES>
 where
-    T: PartialOrd,
     Idx: LinkedListIndex,
-    Kind: kind::Kind,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
 {
     type Target = T;
 
@@ -641,11 +2502,11 @@ where
     }
 }
 
-impl<T, Idx, Kind, const N: usize> DerefMut for FindMut<'_, T, Idx, Kind, N>
+impl<T, Idx, Kind, S, const LANES: usize> DerefMut for FindMut<'_, T, Idx, Kind, S, LANES>
 where
-    T: PartialOrd,
     Idx: LinkedListIndex,
-    Kind: kind::Kind,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.maybe_changed = true;
@@ -654,55 +2515,177 @@ where
     }
 }
 
-// /// Useful for debug during development.
-// impl<T, Idx, Kind, const N: usize> fmt::Debug for FindMut<'_, T, Idx, Kind, N>
-// where
-//     T: PartialOrd + core::fmt::Debug,
-//     Idx: LinkedListIndex,
-//     Kind: kind::Kind,
-// {
-//     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-//         f.debug_struct("FindMut")
-//             .field("prev_index", &self.prev_index.option())
-//             .field("index", &self.index.option())
-//             .field(
-//                 "prev_value",
-//                 &self
-//                     .list
-//                     .read_data_in_node_at(self.prev_index.option().unwrap()),
-//             )
-//             .field(
-//                 "value",
-//                 &self.list.read_data_in_node_at(self.index.option().unwrap()),
-//             )
-//             .finish()
-//     }
-// }
-
-impl<T, Idx, Kind, const N: usize> fmt::Debug for LinkedList<T, Idx, Kind, N>
-where
-    T: PartialOrd + core::fmt::Debug,
-    Idx: LinkedListIndex,
-    Kind: kind::Kind,
+/// Comes from [`LinkedList::peek_mut`].
+pub struct PeekMut<'a, T, Idx, Kind, S = [Node<T, Idx>], const LANES: usize = 0>(
+    FindMut<'a, T, Idx, Kind, S, LANES>,
+)
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>;
+
+impl<T, Idx, Kind, S, const LANES: usize> PeekMut<'_, T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    /// This will pop the first element from the list.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    #[inline]
+    pub fn pop(self) -> T {
+        self.0.pop()
+    }
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> Deref for PeekMut<'_, T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> DerefMut for PeekMut<'_, T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Shows the element and the index of its node.
+impl<T, Idx, Kind, S, const LANES: usize> fmt::Debug for FindMut<'_, T, Idx, Kind, S, LANES>
+where
+    T: fmt::Debug,
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FindMut")
+            .field("index", &self.index.option())
+            .field("value", &**self)
+            .finish()
+    }
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> fmt::Debug for LinkedListInner<T, Idx, Kind, S, LANES>
+where
+    T: core::fmt::Debug,
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.iter()).finish()
     }
 }
 
-impl<T, Idx, Kind, const N: usize> Drop for LinkedList<T, Idx, Kind, N>
+impl<T, Idx, Kind, const N: usize, const LANES: usize> Default
+    for LinkedListInner<T, Idx, Kind, [Node<T, Idx, LANES>; N], LANES>
 where
     Idx: LinkedListIndex,
 {
-    fn drop(&mut self) {
-        let mut index = self.head;
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Clones the list with the elements in sorted order in the first nodes.
+///
+/// Handles to the original list do not point to the same elements in the clone.
+impl<T, Idx, Kind, const N: usize, const LANES: usize> Clone
+    for LinkedListInner<T, Idx, Kind, [Node<T, Idx, LANES>; N], LANES>
+where
+    T: Clone,
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+{
+    fn clone(&self) -> Self {
+        let mut list = Self::new();
+        // Safety: The new list is empty and has the same capacity.
+        unsafe { self.clone_compact_into(&mut list) };
+        list
+    }
+}
+
+/// Lists are compared by their elements, so lists of different capacities or storage can be
+/// equal.
+impl<T, Idx, Kind, S, S2, const LANES: usize> PartialEq<LinkedListInner<T, Idx, Kind, S2, LANES>>
+    for LinkedListInner<T, Idx, Kind, S, LANES>
+where
+    T: PartialEq,
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+    S2: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    fn eq(&self, other: &LinkedListInner<T, Idx, Kind, S2, LANES>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> Eq for LinkedListInner<T, Idx, Kind, S, LANES>
+where
+    T: Eq,
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+}
+
+impl<T, Idx, Kind, S, const LANES: usize> Hash for LinkedListInner<T, Idx, Kind, S, LANES>
+where
+    T: Hash,
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for value in self.iter() {
+            value.hash(state);
+        }
+    }
+}
 
-        while let Some(i) = index.option() {
-            let node = self.node_at_mut(i);
-            index = node.next;
+/// Pushes every element of the iterator.
+///
+/// # Panics
+///
+/// Panics if an element can not be pushed, use [`LinkedList::try_from_iter`] or
+/// `LinkedListVec::try_from_iter` to get the overflow back instead.
+impl<T, Idx, Kind, S, const LANES: usize> Extend<T> for LinkedListInner<T, Idx, Kind, S, LANES>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+    S: ?Sized + storage::Storage<T, Idx, LANES>,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            if let Err(err) = self.push(value) {
+                panic!("failed to extend the list: {}", err);
+            }
+        }
+    }
+}
 
+impl<T, Idx, const LANES: usize> Drop for Node<T, Idx, LANES> {
+    fn drop(&mut self) {
+        // Only nodes holding a value have an odd generation
+        if mem::needs_drop::<T>() && self.generation % 2 == 1 {
             unsafe {
-                ptr::drop_in_place(node.val.as_mut_ptr());
+                ptr::drop_in_place(self.val.as_mut_ptr());
             }
         }
     }
@@ -711,12 +2694,141 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{Bomb, Prio};
+
+    #[test]
+    fn const_new() {
+        static mut _V1: LinkedList<u32, LinkedIndexU8, Max, 100> = LinkedList::new_u8();
+        static mut _V2: LinkedList<u32, LinkedIndexU16, Max, 10_000> = LinkedList::new_u16();
+        static mut _V3: LinkedList<u32, LinkedIndexUsize, Max, 100_000> = LinkedList::new_usize();
+        static mut _V4: LinkedList<u32, LinkedIndexU32, Max, 100_000> = LinkedList::new_u32();
+        static mut _V5: LinkedList<u32, LinkedIndexU16, Max, 100> = LinkedList::new();
+    }
+
+    #[test]
+    fn test_auto_index() {
+        use core::any::TypeId;
+
+        fn same<A: 'static, B: 'static>() -> bool {
+            TypeId::of::<A>() == TypeId::of::<B>()
+        }
+
+        assert!(same::<
+            auto_linked_list!(u32, Min, 0),
+            LinkedList<u32, LinkedIndexU8, Min, 0>,
+        >());
+        assert!(same::<
+            auto_linked_list!(u32, Max, 255),
+            LinkedList<u32, LinkedIndexU8, Max, 255>,
+        >());
+        assert!(same::<
+            auto_linked_list!(u32, Max, 256),
+            LinkedList<u32, LinkedIndexU16, Max, 256>,
+        >());
+        assert!(same::<
+            auto_linked_list!(u8, Min, 65_535),
+            LinkedList<u8, LinkedIndexU16, Min, 65_535>,
+        >());
+        assert!(same::<
+            auto_linked_list!(u8, Min, 65_536),
+            LinkedList<u8, LinkedIndexU32, Min, 65_536>,
+        >());
+
+        const N: usize = 300;
+        static mut _V1: auto_linked_list!(u32, Min, N) = LinkedList::new();
+        static mut _V2: auto_linked_list!(u32, Min, N * 1000) = LinkedList::new();
+
+        let mut ll: auto_linked_list!(u32, MinStable, 2 * N) = LinkedList::new();
+        ll.push(1).unwrap();
+        assert_eq!(ll.capacity(), 600);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_node_size() {
+        use core::mem::size_of;
+
+        assert!(size_of::<Node<u32, LinkedIndexU32>>() < size_of::<Node<u32, LinkedIndexUsize>>());
+    }
+
+    #[test]
+    fn test_new_generic() {
+        fn fill<Idx: LinkedListIndex + fmt::Debug + PartialEq>() {
+            let mut ll: LinkedList<u32, Idx, Min, 5> = LinkedList::new();
+
+            for v in [3, 1, 4, 1, 5] {
+                ll.push(v).unwrap();
+            }
+            assert!(ll.is_full());
+            assert_eq!(ll.push(9), Err(PushError::Full(9)));
+
+            // Released nodes are reused before the list is full again
+            assert_eq!(ll.pop(), Ok(1));
+            assert_eq!(ll.pop_back(), Ok(5));
+            ll.push(2).unwrap();
+            ll.push(6).unwrap();
+            assert!(ll.is_full());
+
+            assert_eq!(ll.into_iter().collect::<Vec<_>>(), [1, 2, 3, 4, 6]);
+        }
+
+        fill::<LinkedIndexU8>();
+        fill::<LinkedIndexU16>();
+        fill::<LinkedIndexU32>();
+        fill::<LinkedIndexUsize>();
+    }
+
+    #[test]
+    fn test_view() {
+        fn fill(ll: &mut LinkedListView<u32, LinkedIndexU8, Min>) -> Handle<LinkedIndexU8> {
+            while !ll.is_full() {
+                ll.push(ll.len() as u32 * 7 % 5).unwrap();
+            }
+            assert_eq!(ll.push(9), Err(PushError::Full(9)));
+
+            ll.find_mut(|v| *v == 0).unwrap().pop();
+            ll.push(3).unwrap()
+        }
+
+        let mut small: LinkedList<u32, LinkedIndexU8, Min, 3> = LinkedList::new_u8();
+        let mut large: LinkedList<u32, LinkedIndexU8, Min, 6> = LinkedList::new_u8();
+
+        let h = fill(&mut small);
+        assert_eq!(small.as_view().capacity(), 3);
+        assert_eq!(format!("{:?}", small.as_view()), "[2, 3, 4]");
+        assert_eq!(small.remove(h), Ok(3));
+
+        let h = fill(large.as_mut_view());
+        assert_eq!(large.capacity(), 6);
+        assert!(large.iter().eq(&[0, 1, 2, 3, 3, 4]));
+        assert_eq!(large.get(h), Ok(&3));
+
+        // Handles are checked against the capacity of the view
+        assert_eq!(small.get(h), Err(StaleHandle));
+    }
 
     #[test]
-    fn const_new() {
-        static mut _V1: LinkedList<u32, LinkedIndexU8, Max, 100> = LinkedList::new_u8();
-        static mut _V2: LinkedList<u32, LinkedIndexU16, Max, 10_000> = LinkedList::new_u16();
-        static mut _V3: LinkedList<u32, LinkedIndexUsize, Max, 100_000> = LinkedList::new_usize();
+    fn max_capacity() {
+        static mut V1: LinkedList<u32, LinkedIndexU8, Max, 255> = LinkedList::new_u8();
+        static mut V2: LinkedList<u32, LinkedIndexU16, Min, 65_535> = LinkedList::new_u16();
+
+        let v1 = unsafe { &mut *ptr::addr_of_mut!(V1) };
+        let v2 = unsafe { &mut *ptr::addr_of_mut!(V2) };
+
+        // Every index below the end of list marker can be used
+        for v in 0..255 {
+            v1.push(v).unwrap();
+        }
+        for v in (0..65_535).rev() {
+            v2.push(v).unwrap();
+        }
+
+        assert!(v1.is_full());
+        assert!(v2.is_full());
+        assert_eq!(v1.pop_back(), Ok(0));
+        assert_eq!(v2.pop_back(), Ok(65_534));
+        assert_eq!(v1.len(), 254);
+        assert_eq!(v2.len(), 65_534);
     }
 
     #[test]
@@ -779,7 +2891,287 @@ mod tests {
         // This won't fit
         let r = ll.push(4);
 
-        assert_eq!(r, Err(4));
+        assert_eq!(r, Err(PushError::Full(4)));
+    }
+
+    #[test]
+    fn test_errors() {
+        #[derive(Debug, PartialEq)]
+        enum MoveError {
+            Pop(PopError),
+            Push(PushError<u32>),
+        }
+
+        impl From<PopError> for MoveError {
+            fn from(e: PopError) -> Self {
+                MoveError::Pop(e)
+            }
+        }
+
+        impl From<PushError<u32>> for MoveError {
+            fn from(e: PushError<u32>) -> Self {
+                MoveError::Push(e)
+            }
+        }
+
+        type List = LinkedList<u32, LinkedIndexU8, Min, 1>;
+
+        fn move_first(from: &mut List, to: &mut List) -> Result<(), MoveError> {
+            to.push(from.pop()?)?;
+            Ok(())
+        }
+
+        let mut a: List = LinkedList::new();
+        let mut b: List = LinkedList::new();
+
+        assert_eq!(move_first(&mut a, &mut b), Err(MoveError::Pop(PopError)));
+        a.push(1).unwrap();
+        b.push(2).unwrap();
+        assert_eq!(
+            move_first(&mut a, &mut b),
+            Err(MoveError::Push(PushError::Full(1)))
+        );
+
+        assert_eq!(PushError::Full(1).into_inner(), 1);
+        assert_eq!(PopError.to_string(), "the list is empty");
+        assert_eq!(PushError::Full(1).to_string(), "the list is full");
+        assert_eq!(PushError::Incomparable(1).into_inner(), 1);
+        assert_eq!(
+            PushError::Incomparable(1).to_string(),
+            "the element can not be compared with itself"
+        );
+        assert_eq!(
+            StaleHandle.to_string(),
+            "handle does not point to an element in the list"
+        );
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut ll: LinkedList<Prio, LinkedIndexU8, Max, 6> = LinkedList::new();
+        let mut handles = Vec::new();
+        for (i, tag) in "abcdef".chars().enumerate() {
+            handles.push(ll.push(Prio(i as u32 % 3, tag)).unwrap());
+        }
+        ll.remove(handles[0]).unwrap();
+        ll.remove(handles[4]).unwrap();
+
+        let mut clone = ll.clone();
+        assert_eq!(format!("{:?}", clone), format!("{:?}", ll));
+
+        // The elements are in the first nodes, in order
+        assert_eq!(clone.links.head.option(), Some(0));
+        for i in 0..clone.len() - 1 {
+            assert_eq!(clone.node_at(i).next.option(), Some(i + 1));
+        }
+        assert_eq!(clone.links.tail.option(), Some(clone.len() - 1));
+
+        // The clone keeps sorting new elements in
+        clone.push(Prio(1, 'g')).unwrap();
+        clone.push(Prio(3, 'h')).unwrap();
+        assert!(clone.is_full());
+        assert_eq!(pop_tags(clone), "hfcgbd");
+    }
+
+    #[test]
+    fn test_eq_hash() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash<H: Hash + ?Sized>(value: &H) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut a: LinkedList<u32, LinkedIndexU8, Min, 4> = LinkedList::new();
+        let mut b: LinkedList<u32, LinkedIndexU8, Min, 8> = LinkedList::new();
+        a.extend([3, 1, 2]);
+        b.extend([2, 3, 1]);
+
+        // Lists are equal by their elements, independent of capacity and insertion history
+        assert_eq!(a, b);
+        assert_eq!(a.as_view(), &*a.clone());
+        assert_eq!(hash(&a), hash(&b));
+        assert_eq!(hash(&a), hash(a.as_view()));
+
+        b.push(4).unwrap();
+        assert_ne!(a, b);
+        b.pop_back().unwrap();
+        b.pop().unwrap();
+        assert_ne!(a, b);
+        assert_ne!(hash(&a), hash(&b));
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut ll: LinkedList<u32, LinkedIndexU8, Max, 4> = LinkedList::new();
+        ll.extend([1, 3]);
+        ll.as_mut_view().extend(Some(2));
+        assert!(ll.iter().eq(&[3, 2, 1]));
+
+        let r = std::panic::catch_unwind(move || ll.extend([4, 5]));
+        assert_eq!(
+            *r.unwrap_err().downcast::<String>().unwrap(),
+            "failed to extend the list: the list is full"
+        );
+    }
+
+    #[test]
+    fn test_try_from_iter() {
+        type List = LinkedList<f32, LinkedIndexU8, RejectIncomparable<Min>, 4>;
+
+        let ll = List::try_from_iter([2.0, 1.0]).unwrap();
+        assert!(ll.iter().eq(&[1.0, 2.0]));
+
+        let ll = List::try_from_iter([]).unwrap();
+        assert!(ll.is_empty());
+
+        // The overflow starts with the rejected element
+        let (ll, overflow) = List::try_from_iter([2.0, 1.0, f32::NAN, 0.0]).unwrap_err();
+        assert!(ll.iter().eq(&[1.0, 2.0]));
+        let overflow: Vec<_> = overflow.collect();
+        assert!(overflow[0].is_nan());
+        assert_eq!(overflow[1..], [0.0]);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        use core::cell::Cell;
+        use std::rc::Rc;
+
+        fn assert_send<T: ?Sized + Send>() {}
+        fn assert_sync<T: ?Sized + Sync>() {}
+
+        /// A comparator that is neither `Send` nor `Sync`.
+        struct LocalCompare(PhantomData<Rc<()>>);
+
+        impl Compare<u32> for LocalCompare {
+            fn compare(a: &u32, b: &u32) -> Ordering {
+                a.cmp(b)
+            }
+        }
+
+        type Local = Stable<By<LocalCompare>>;
+
+        // The kind does not matter
+        assert_send::<LinkedList<u32, LinkedIndexU8, Local, 4>>();
+        assert_sync::<LinkedList<u32, LinkedIndexU8, Local, 4>>();
+        assert_send::<LinkedListView<u32, LinkedIndexU8, Local>>();
+        assert_sync::<LinkedListView<u32, LinkedIndexU8, Local>>();
+        assert_send::<LinkedListPool<u32, LinkedIndexU8, Local, 4, 2>>();
+        assert_sync::<LinkedListPool<u32, LinkedIndexU8, Local, 4, 2>>();
+        assert_send::<FindMut<'static, u32, LinkedIndexU8, Local>>();
+        assert_sync::<FindMut<'static, u32, LinkedIndexU8, Local>>();
+        assert_send::<PeekMut<'static, u32, LinkedIndexU8, Local>>();
+        assert_sync::<PeekMut<'static, u32, LinkedIndexU8, Local>>();
+        assert_send::<Drain<'static, u32, LinkedIndexU8, Local>>();
+        assert_sync::<Drain<'static, u32, LinkedIndexU8, Local>>();
+        assert_send::<Iter<'static, u32, LinkedIndexU8, Local>>();
+        assert_sync::<Iter<'static, u32, LinkedIndexU8, Local>>();
+        assert_send::<IntoIter<u32, LinkedIndexU8, Local, [Node<u32, LinkedIndexU8>; 4]>>();
+        assert_sync::<IntoIter<u32, LinkedIndexU8, Local, [Node<u32, LinkedIndexU8>; 4]>>();
+
+        // `Cell` is `Send` but not `Sync`
+        assert_send::<LinkedList<Cell<u32>, LinkedIndexU8, Min, 4>>();
+        assert_send::<LinkedListPool<Cell<u32>, LinkedIndexU8, Min, 4, 2>>();
+        assert_send::<FindMut<'static, Cell<u32>, LinkedIndexU8, Min>>();
+    }
+
+    #[test]
+    fn test_find_mut_send() {
+        let mut ll: LinkedList<u32, LinkedIndexU8, Min, 4> = LinkedList::new();
+        ll.extend([1, 2, 3]);
+
+        // The element is resorted on the thread that drops the guard
+        let mut find = ll.find_mut(|v| *v == 1).unwrap();
+        std::thread::scope(|s| {
+            s.spawn(move || *find = 4);
+        });
+
+        assert!(ll.iter().eq(&[2, 3, 4]));
+    }
+
+    #[test]
+    fn test_incomparable_last() {
+        let mut ll: LinkedList<f32, LinkedIndexU8, IncomparableLast<Max>, 8> = LinkedList::new();
+
+        for v in [f32::NAN, 2.0, f32::NAN, 1.0, 3.0, f32::NAN] {
+            ll.push(v).unwrap();
+        }
+
+        let values: Vec<_> = ll.iter().copied().collect();
+        assert_eq!(values[..3], [3.0, 2.0, 1.0]);
+        assert!(values[3..].iter().all(|v| v.is_nan()));
+
+        // An element that becomes NaN is moved to the end
+        let handle = ll.push(2.5).unwrap();
+        *ll.update(handle).unwrap() = f32::NAN;
+        assert_eq!(ll.iter().position(|v| v.is_nan()), Some(3));
+
+        // And back into place once it is comparable again
+        *ll.update(handle).unwrap() = 0.0;
+        assert_eq!(ll.iter().position(|v| *v == 0.0), Some(3));
+    }
+
+    #[test]
+    fn test_incomparable_last_stable() {
+        let mut ll: LinkedList<(f64, char), LinkedIndexU8, Stable<IncomparableLast<Min>>, 8> =
+            LinkedList::new();
+
+        ll.push((f64::NAN, 'a')).unwrap();
+        ll.push((1.0, 'b')).unwrap();
+        ll.push((f64::NAN, 'c')).unwrap();
+        ll.push((0.5, 'd')).unwrap();
+        ll.push((f64::NAN, 'e')).unwrap();
+
+        let tags: String = ll.iter().map(|v| v.1).collect();
+        assert_eq!(tags, "dbace");
+    }
+
+    #[test]
+    fn test_reject_incomparable() {
+        let mut ll: LinkedList<f64, LinkedIndexU8, RejectIncomparable<Min>, 3> = LinkedList::new();
+
+        assert!(matches!(
+            ll.push(f64::NAN),
+            Err(PushError::Incomparable(v)) if v.is_nan()
+        ));
+        assert!(matches!(
+            ll.push_evict(f64::NAN),
+            Err(PushError::Incomparable(v)) if v.is_nan()
+        ));
+        assert!(ll.is_empty());
+
+        ll.push(2.0).unwrap();
+        ll.push(1.0).unwrap();
+        ll.push(f64::INFINITY).unwrap();
+
+        // A full list still reports that the element is incomparable
+        assert!(matches!(ll.push(f64::NAN), Err(PushError::Incomparable(_))));
+        assert_eq!(ll.push(0.0), Err(PushError::Full(0.0)));
+
+        // An element made incomparable in the list is sorted last
+        let mut find = ll.find_mut(|v| *v == 1.0).unwrap();
+        *find = f64::NAN;
+        find.finish();
+
+        assert_eq!(ll.pop(), Ok(2.0));
+        assert_eq!(ll.pop(), Ok(f64::INFINITY));
+        assert!(ll.pop().unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_total() {
+        let mut ll: LinkedList<i32, LinkedIndexU8, Total<Min>, 4> = LinkedList::new();
+        let mut lm: LinkedList<i32, LinkedIndexU8, Stable<Total<Max>>, 4> = LinkedList::new();
+
+        for v in [3, -1, 2, 0] {
+            ll.push(v).unwrap();
+            lm.push(v).unwrap();
+        }
+
+        assert!(ll.iter().copied().eq([-1, 0, 2, 3]));
+        assert!(lm.iter().copied().eq([3, 2, 0, -1]));
     }
 
     #[test]
@@ -809,7 +3201,6 @@ mod tests {
         assert_eq!(ll.peek().unwrap(), &1002);
     }
 
-    #[test]
     #[test]
     fn test_updating_1() {
         let mut ll: LinkedList<u32, LinkedIndexUsize, Max, 3> = LinkedList::new_usize();
@@ -832,4 +3223,591 @@ mod tests {
 
         assert_eq!(ll.peek().unwrap(), &1001);
     }
+
+    #[test]
+    fn test_handles() {
+        let mut ll: LinkedList<u32, LinkedIndexUsize, Min, 4> = LinkedList::new_usize();
+        let h1 = ll.push(1).unwrap();
+        let h2 = ll.push(2).unwrap();
+        let h3 = ll.push(3).unwrap();
+
+        assert_eq!(ll.get(h1), Ok(&1));
+        assert_eq!(ll.get(h2), Ok(&2));
+        assert_eq!(ll.get(h3), Ok(&3));
+
+        // Move the head to the back, all handles stay valid
+        *ll.update(h1).unwrap() += 10;
+
+        assert_eq!(ll.get(h1), Ok(&11));
+        assert_eq!(ll.peek(), Some(&2));
+
+        // Remove from the middle
+        assert_eq!(ll.remove(h3), Ok(3));
+
+        let h4 = ll.push(0).unwrap();
+        assert_eq!(ll.get(h4), Ok(&0));
+
+        assert_eq!(ll.iter().copied().collect::<Vec<_>>(), [0, 2, 11]);
+
+        // Remove the tail and the head
+        assert_eq!(unsafe { ll.remove_unchecked(h1) }, 11);
+        assert_eq!(ll.remove(h4), Ok(0));
+        assert_eq!(ll.pop(), Ok(2));
+        assert!(ll.is_empty());
+    }
+
+    #[test]
+    fn test_stale_handles() {
+        let mut ll: LinkedList<u32, LinkedIndexU8, Min, 2> = LinkedList::new_u8();
+        let h1 = ll.push(1).unwrap();
+
+        // The node of `h1` is reused by `h2`
+        assert_eq!(ll.pop(), Ok(1));
+        let h2 = ll.push(2).unwrap();
+
+        assert!(!ll.contains(h1));
+        assert!(ll.contains(h2));
+        assert_eq!(ll.get(h1), Err(StaleHandle));
+        assert!(ll.update(h1).is_err());
+        assert_eq!(ll.remove(h1), Err(StaleHandle));
+        assert_eq!(ll.get(h2), Ok(&2));
+
+        // Popping through `find_mut` also invalidates the handle
+        ll.find_mut(|v| *v == 2).unwrap().pop();
+        assert_eq!(ll.get(h2), Err(StaleHandle));
+
+        // Handles from a larger list are out of range
+        let mut large: LinkedList<u32, LinkedIndexU8, Min, 4> = LinkedList::new_u8();
+        large.push(1).unwrap();
+        large.push(2).unwrap();
+        let h3 = large.push(3).unwrap();
+        assert_eq!(ll.get(h3), Err(StaleHandle));
+    }
+
+    #[test]
+    fn test_stale_handle_node_reuse() {
+        let mut ll: LinkedList<u32, LinkedIndexU8, Min, 16> = LinkedList::new_u8();
+        let stale = ll.push(1).unwrap();
+        assert_eq!(ll.pop(), Ok(1));
+
+        // Released nodes are reused first, so every push takes the node of `stale`. After this
+        // many reuses an 8 or 16 bit generation would be back at the one of `stale`.
+        for v in 0..32_767 {
+            ll.push(v).unwrap();
+            assert_eq!(ll.pop(), Ok(v));
+        }
+        let live = ll.push(999).unwrap();
+
+        assert_eq!(ll.get(stale), Err(StaleHandle));
+        assert_eq!(ll.remove(stale), Err(StaleHandle));
+        assert_eq!(ll.remove(live), Ok(999));
+    }
+
+    #[test]
+    fn test_find_mut_pop_after_change() {
+        let mut ll: LinkedList<u32, LinkedIndexUsize, Max, 3> = LinkedList::new_usize();
+        ll.push(1).unwrap();
+        ll.push(2).unwrap();
+        ll.push(3).unwrap();
+
+        let mut find = ll.find_mut(|v| *v == 2).unwrap();
+        *find += 1000;
+        assert_eq!(find.pop(), 1002);
+
+        assert_eq!(ll.pop(), Ok(3));
+        assert_eq!(ll.pop(), Ok(1));
+        assert_eq!(ll.pop(), Err(PopError));
+    }
+
+    #[test]
+    fn test_find_mut_debug() {
+        let mut ll: LinkedList<u32, LinkedIndexU8, Max, 3> = LinkedList::new_u8();
+        ll.push(1).unwrap();
+        ll.push(2).unwrap();
+
+        let find = ll.find_mut(|v| *v == 2).unwrap();
+        assert_eq!(
+            format!("{:?}", find),
+            "FindMut { index: Some(1), value: 2 }"
+        );
+    }
+
+    #[test]
+    fn test_double_ended() {
+        let mut ll: LinkedList<u32, LinkedIndexU8, Min, 5> = LinkedList::new_u8();
+        assert_eq!(ll.peek_back(), None);
+
+        for v in [3, 1, 4, 1, 5] {
+            ll.push(v).unwrap();
+        }
+
+        assert_eq!(ll.peek(), Some(&1));
+        assert_eq!(ll.peek_back(), Some(&5));
+
+        // Moving the tail to the front updates both ends
+        let mut find = ll.find_mut(|v| *v == 5).unwrap();
+        *find = 0;
+        find.finish();
+
+        assert_eq!(ll.peek(), Some(&0));
+        assert_eq!(ll.peek_back(), Some(&4));
+
+        assert_eq!(ll.pop_back(), Ok(4));
+        assert_eq!(ll.pop(), Ok(0));
+        assert_eq!(ll.pop_back(), Ok(3));
+        assert_eq!(ll.pop_back(), Ok(1));
+        assert_eq!(ll.peek(), Some(&1));
+        assert_eq!(ll.peek_back(), Some(&1));
+        assert_eq!(ll.pop_back(), Ok(1));
+        assert_eq!(ll.pop_back(), Err(PopError));
+        assert_eq!(ll.peek(), None);
+        assert_eq!(ll.peek_back(), None);
+
+        // The freed nodes are reusable from both ends
+        ll.push(7).unwrap();
+        assert_eq!(ll.peek(), Some(&7));
+        assert_eq!(ll.peek_back(), Some(&7));
+    }
+
+    #[test]
+    fn test_push_evict() {
+        let mut ll: LinkedList<u32, LinkedIndexU8, Min, 3> = LinkedList::new_u8();
+
+        assert_eq!(ll.push_evict(5), Ok(None));
+        assert_eq!(ll.push_evict(3), Ok(None));
+        let h = ll.push(8).unwrap();
+
+        // Keeps the 3 smallest values
+        assert_eq!(ll.push_evict(9), Err(PushError::Full(9)));
+        assert_eq!(ll.push_evict(8), Err(PushError::Full(8)));
+        assert_eq!(ll.push_evict(1), Ok(Some(8)));
+        assert_eq!(ll.push_evict(4), Ok(Some(5)));
+
+        assert_eq!(ll.iter().copied().collect::<Vec<_>>(), [1, 3, 4]);
+
+        // Handles to evicted elements become stale
+        assert_eq!(ll.get(h), Err(StaleHandle));
+
+        // Nothing fits in a zero sized list
+        let mut ll: LinkedList<u32, LinkedIndexU8, Min, 0> = LinkedList::new_u8();
+        assert_eq!(ll.push_evict(1), Err(PushError::Full(1)));
+    }
+
+    #[test]
+    fn test_find_mut_ordered() {
+        let mut ll: LinkedList<u32, LinkedIndexU16, Min, 100> = LinkedList::new_u16();
+
+        for v in (0..100).rev() {
+            ll.push(v * 2).unwrap();
+        }
+
+        // Odd values are not in the list
+        assert!(ll.find_mut_ordered(&51).is_none());
+        assert!(ll.find_mut_ordered(&199).is_none());
+
+        let mut find = ll.find_mut_ordered(&50).unwrap();
+        assert_eq!(*find, 50);
+        *find = 51;
+        find.finish();
+
+        assert!(ll.find_mut_ordered(&50).is_none());
+        assert_eq!(ll.find_mut_ordered(&51).unwrap().pop(), 51);
+        assert_eq!(ll.find_mut_ordered(&198).unwrap().pop(), 198);
+        assert_eq!(ll.find_mut_ordered(&0).unwrap().pop(), 0);
+
+        let values: Vec<_> = ll.iter().copied().collect();
+        assert_eq!(values.len(), 97);
+        assert!(values.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    fn pop_tags<Kind: kind::Kind<Prio>, const N: usize>(
+        mut ll: LinkedList<Prio, LinkedIndexU8, Kind, N>,
+    ) -> String {
+        core::iter::from_fn(|| ll.pop_first())
+            .map(|p| p.1)
+            .collect()
+    }
+
+    #[test]
+    fn test_tie_order() {
+        let values = [(1, 'a'), (0, 'b'), (1, 'c'), (2, 'd'), (1, 'e'), (0, 'f')];
+
+        let mut min: LinkedList<_, LinkedIndexU8, Min, 6> = LinkedList::new_u8();
+        let mut min_stable: LinkedList<_, LinkedIndexU8, MinStable, 6> = LinkedList::new_u8();
+        let mut max: LinkedList<_, LinkedIndexU8, Max, 6> = LinkedList::new_u8();
+        let mut max_stable: LinkedList<_, LinkedIndexU8, MaxStable, 6> = LinkedList::new_u8();
+
+        for (prio, tag) in values {
+            min.push(Prio(prio, tag)).unwrap();
+            min_stable.push(Prio(prio, tag)).unwrap();
+            max.push(Prio(prio, tag)).unwrap();
+            max_stable.push(Prio(prio, tag)).unwrap();
+        }
+
+        assert_eq!(pop_tags(min), "fbecad");
+        assert_eq!(pop_tags(min_stable), "bfaced");
+        assert_eq!(pop_tags(max), "decafb");
+        assert_eq!(pop_tags(max_stable), "dacebf");
+    }
+
+    #[test]
+    fn test_tie_order_resort() {
+        let mut ll: LinkedList<_, LinkedIndexU8, MinStable, 4> = LinkedList::new_u8();
+        let a = ll.push(Prio(1, 'a')).unwrap();
+        ll.push(Prio(1, 'b')).unwrap();
+        ll.push(Prio(2, 'c')).unwrap();
+
+        // A resorted element is placed after the equal elements, as if it was pushed again
+        ll.update(a).unwrap().0 = 2;
+        assert_eq!(pop_tags(ll), "bca");
+
+        // Evicting keeps the earlier of equal elements
+        let mut ll: LinkedList<_, LinkedIndexU8, MinStable, 2> = LinkedList::new_u8();
+        ll.push(Prio(1, 'a')).unwrap();
+        ll.push(Prio(2, 'b')).unwrap();
+        assert_eq!(
+            ll.push_evict(Prio(2, 'c')).map_err(|e| e.into_inner().1),
+            Err('c')
+        );
+        assert_eq!(
+            ll.push_evict(Prio(1, 'd')).map(|p| p.map(|p| p.1)),
+            Ok(Some('b'))
+        );
+        assert_eq!(pop_tags(ll), "ad");
+    }
+
+    struct ByPrio;
+
+    impl Compare<Prio> for ByPrio {
+        fn compare(a: &Prio, b: &Prio) -> core::cmp::Ordering {
+            b.0.cmp(&a.0)
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let values = [(1, 'a'), (0, 'b'), (1, 'c'), (2, 'd'), (1, 'e'), (0, 'f')];
+
+        let mut by: LinkedList<_, LinkedIndexU8, By<ByPrio>, 6> = LinkedList::new_u8();
+        let mut by_stable: LinkedList<_, LinkedIndexU8, Stable<By<ByPrio>>, 6> =
+            LinkedList::new_u8();
+
+        for (prio, tag) in values {
+            by.push(Prio(prio, tag)).unwrap();
+            by_stable.push(Prio(prio, tag)).unwrap();
+        }
+
+        // Sorts the same as `Max`
+        assert_eq!(by.find_mut_ordered(&Prio(1, 'x')).map(|p| p.1), Some('e'));
+        assert_eq!(pop_tags(by), "decafb");
+        assert_eq!(pop_tags(by_stable), "dacebf");
+    }
+
+    #[test]
+    fn test_len() {
+        let mut ll: LinkedList<u32, LinkedIndexU8, Min, 4> = LinkedList::new_u8();
+        assert_eq!(ll.len(), 0);
+        assert_eq!(ll.capacity(), 4);
+
+        let h = ll.push(1).unwrap();
+        ll.push(2).unwrap();
+        ll.push(3).unwrap();
+        ll.push(4).unwrap();
+        assert_eq!(ll.len(), 4);
+
+        // Rejected and evicting pushes keep the length
+        assert!(ll.push(5).is_err());
+        assert_eq!(ll.push_evict(0), Ok(Some(4)));
+        assert_eq!(ll.len(), 4);
+
+        // Resorting keeps the length
+        *ll.find_mut(|v| *v == 2).unwrap() = 10;
+        assert_eq!(ll.len(), 4);
+
+        ll.find_mut(|v| *v == 10).unwrap().pop();
+        assert_eq!(ll.len(), 3);
+        ll.remove(h).unwrap();
+        assert_eq!(ll.len(), 2);
+        ll.pop_back().unwrap();
+        assert_eq!(ll.len(), 1);
+        ll.pop().unwrap();
+        assert_eq!(ll.len(), 0);
+        assert!(ll.pop().is_err());
+        assert_eq!(ll.len(), 0);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut ll: LinkedList<u32, LinkedIndexU8, Min, 8> = LinkedList::new_u8();
+        let handles: Vec<_> = (0..8).map(|v| ll.push(v).unwrap()).collect();
+
+        ll.retain(|v| v % 3 != 0);
+
+        assert_eq!(ll.iter().copied().collect::<Vec<_>>(), [1, 2, 4, 5, 7]);
+        assert_eq!(ll.len(), 5);
+        assert_eq!(ll.get(handles[3]), Err(StaleHandle));
+        assert_eq!(ll.get(handles[4]), Ok(&4));
+        assert_eq!(ll.peek_back(), Some(&7));
+
+        // The removed nodes are reused
+        for v in [10, 11, 12] {
+            ll.push(v).unwrap();
+        }
+        assert!(ll.is_full());
+
+        ll.retain(|_| false);
+        assert!(ll.is_empty());
+        assert_eq!(ll.peek_back(), None);
+    }
+
+    #[test]
+    fn test_extract_if() {
+        let mut ll: LinkedList<u32, LinkedIndexU8, Min, 8> = LinkedList::new_u8();
+        for v in 0..8 {
+            ll.push(v).unwrap();
+        }
+
+        // Stop early, the rest is kept
+        {
+            let mut extract = ll.extract_if(|v| v % 2 == 1);
+            assert_eq!(extract.next(), Some(1));
+            assert_eq!(extract.next(), Some(3));
+        }
+
+        assert_eq!(ll.iter().copied().collect::<Vec<_>>(), [0, 2, 4, 5, 6, 7]);
+
+        let extracted: Vec<_> = ll.extract_if(|v| *v >= 5).collect();
+        assert_eq!(extracted, [5, 6, 7]);
+        assert_eq!(ll.iter().copied().collect::<Vec<_>>(), [0, 2, 4]);
+        assert_eq!(ll.peek_back(), Some(&4));
+        assert_eq!(ll.len(), 3);
+    }
+
+    #[test]
+    fn test_retain_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut ll: LinkedList<_, LinkedIndexU8, Min, 6> = LinkedList::new_u8();
+        for v in 0..6 {
+            ll.push(Prio(v, 'a')).unwrap();
+        }
+
+        let mut rcs: LinkedList<_, LinkedIndexU8, Min, 6> = LinkedList::new_u8();
+        for _ in 0..6 {
+            rcs.push(Rc::clone(&counter)).unwrap();
+        }
+
+        let r = catch_unwind(AssertUnwindSafe(|| {
+            ll.retain(|p| {
+                assert!(p.0 != 3);
+                p.0 % 2 == 0
+            })
+        }));
+        assert!(r.is_err());
+
+        // Visited elements are removed, the rest are kept and the list is consistent
+        assert_eq!(ll.iter().map(|p| p.0).collect::<Vec<_>>(), [0, 2, 3, 4, 5]);
+        assert_eq!(ll.len(), 5);
+        assert_eq!(ll.pop_back().map(|p| p.0), Ok(5));
+
+        // Removed elements are dropped exactly once
+        let mut visited = 0;
+        let r = catch_unwind(AssertUnwindSafe(|| {
+            rcs.retain(|_| {
+                visited += 1;
+                assert!(visited != 4);
+                false
+            })
+        }));
+        assert!(r.is_err());
+        assert_eq!(Rc::strong_count(&counter), 1 + 3);
+        drop(rcs);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    fn bomb_values<Kind: kind::Kind<Bomb>, const N: usize>(
+        ll: &LinkedList<Bomb, LinkedIndexU8, Kind, N>,
+    ) -> Vec<u32> {
+        ll.iter().map(|b| b.0).collect()
+    }
+
+    #[test]
+    fn test_push_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut ll: LinkedList<_, LinkedIndexU8, Min, 4> = LinkedList::new_u8();
+        ll.push(Bomb(2, counter.clone())).unwrap();
+        ll.push(Bomb(4, counter.clone())).unwrap();
+
+        for _ in 0..10 {
+            let r = catch_unwind(AssertUnwindSafe(|| ll.push(Bomb(0, counter.clone()))));
+            assert!(r.is_err());
+        }
+
+        // The values are dropped and the list is unchanged
+        assert_eq!(Rc::strong_count(&counter), 1 + 2);
+        assert_eq!(bomb_values(&ll), [2, 4]);
+        assert_eq!(ll.len(), 2);
+
+        // No nodes were lost
+        ll.push(Bomb(3, counter.clone())).unwrap();
+        ll.push(Bomb(1, counter.clone())).unwrap();
+        assert!(ll.is_full());
+        assert_eq!(bomb_values(&ll), [1, 2, 3, 4]);
+
+        drop(ll);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn test_resort_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut ll: LinkedList<_, LinkedIndexU8, MaxStable, 4> = LinkedList::new_u8();
+        ll.push(Bomb(1, counter.clone())).unwrap();
+        let handle = ll.push(Bomb(2, counter.clone())).unwrap();
+        ll.push(Bomb(3, counter.clone())).unwrap();
+
+        let r = catch_unwind(AssertUnwindSafe(|| {
+            let mut update = ll.update(handle).unwrap();
+            update.0 = 0;
+        }));
+        assert!(r.is_err());
+
+        // The element is removed and dropped, the rest of the list is intact
+        assert_eq!(Rc::strong_count(&counter), 1 + 2);
+        assert_eq!(ll.get(handle), Err(StaleHandle));
+        assert_eq!(bomb_values(&ll), [3, 1]);
+        assert_eq!(ll.len(), 2);
+
+        let r = catch_unwind(AssertUnwindSafe(|| {
+            let mut peek = ll.peek_mut().unwrap();
+            // `peek.0` would be the field of `PeekMut`
+            (*peek).0 = 0;
+        }));
+        assert!(r.is_err());
+        assert_eq!(bomb_values(&ll), [1]);
+
+        // No nodes were lost
+        for v in 4..7 {
+            ll.push(Bomb(v, counter.clone())).unwrap();
+        }
+        assert!(ll.is_full());
+        assert_eq!(bomb_values(&ll), [6, 5, 4, 1]);
+
+        drop(ll);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn test_clone_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut ll: LinkedList<_, LinkedIndexU8, Min, 4> = LinkedList::new_u8();
+        for v in [1, 13, 2, 20] {
+            ll.push(Bomb(v, counter.clone())).unwrap();
+        }
+
+        // The elements cloned before the panic are dropped with the partial clone
+        let r = catch_unwind(AssertUnwindSafe(|| ll.clone()));
+        assert!(r.is_err());
+        assert_eq!(Rc::strong_count(&counter), 1 + 4);
+        assert_eq!(bomb_values(&ll), [1, 2, 13, 20]);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut ll: LinkedList<_, LinkedIndexU8, Max, 4> = LinkedList::new_u8();
+        for v in 0..4 {
+            ll.push((v, Rc::clone(&counter))).unwrap();
+        }
+
+        let mut iter = ll.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next().map(|v| v.0), Some(3));
+        assert_eq!(iter.next_back().map(|v| v.0), Some(0));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(Rc::strong_count(&counter), 1 + 2);
+
+        // The remaining elements are dropped with the iterator
+        drop(iter);
+        assert_eq!(Rc::strong_count(&counter), 1);
+
+        let mut ll: LinkedList<u32, LinkedIndexU8, Min, 4> = LinkedList::new_u8();
+        ll.push(2).unwrap();
+        ll.push(1).unwrap();
+        assert_eq!((&ll).into_iter().collect::<Vec<_>>(), [&1, &2]);
+    }
+
+    #[test]
+    fn test_drain() {
+        use std::rc::Rc;
+
+        let counter = Rc::new(());
+        let mut ll: LinkedList<_, LinkedIndexU8, Max, 4> = LinkedList::new_u8();
+        for v in 0..4 {
+            ll.push((v, Rc::clone(&counter))).unwrap();
+        }
+
+        let mut drain = ll.drain();
+        assert_eq!(drain.len(), 4);
+        assert_eq!(drain.next().map(|v| v.0), Some(3));
+        assert_eq!(Rc::strong_count(&counter), 1 + 3);
+
+        // The remaining elements are removed and dropped with the iterator
+        drop(drain);
+        assert_eq!(Rc::strong_count(&counter), 1);
+        assert!(ll.is_empty());
+        assert_eq!(ll.len(), 0);
+
+        // The list can be filled again
+        for v in 0..4 {
+            ll.push((v, Rc::clone(&counter))).unwrap();
+        }
+        assert!(ll.is_full());
+        ll.clear();
+        assert!(ll.is_empty());
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn test_peek_mut() {
+        let mut ll: LinkedList<u32, LinkedIndexU8, Min, 3> = LinkedList::new_u8();
+        assert!(ll.peek_mut().is_none());
+
+        let h1 = ll.push(1).unwrap();
+        ll.push(2).unwrap();
+        ll.push(3).unwrap();
+
+        // Only reading does not resort
+        assert_eq!(*ll.peek_mut().unwrap(), 1);
+        assert_eq!(ll.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+
+        // Moving the head to the back keeps its handle
+        *ll.peek_mut().unwrap() = 10;
+        assert_eq!(ll.iter().copied().collect::<Vec<_>>(), [2, 3, 10]);
+        assert_eq!(ll.peek_back(), Some(&10));
+        assert_eq!(ll.get(h1), Ok(&10));
+
+        // Changing the head without moving it
+        *ll.peek_mut().unwrap() = 0;
+        assert_eq!(ll.iter().copied().collect::<Vec<_>>(), [0, 3, 10]);
+
+        let mut peek = ll.peek_mut().unwrap();
+        *peek = 20;
+        assert_eq!(peek.pop(), 20);
+        assert_eq!(ll.iter().copied().collect::<Vec<_>>(), [3, 10]);
+        assert_eq!(ll.len(), 2);
+    }
 }
@@ -0,0 +1,874 @@
+//! A list with a lock-free intake for several producers, enabled with the `mpsc` feature.
+//!
+//! Every node has one atomic `next` link, used by whichever chain the node is in:
+//!
+//! - The free stack, pushed by the consumer and popped by the producers. The head holds the
+//!   index of the top node in the lowest `ceil(log2(N + 1))` bits, and a tag in the rest that is
+//!   bumped on every push, so a producer holding an old head does not pop a node that was taken
+//!   and released in the meantime (ABA). The head is an `AtomicU64` where the target has one,
+//!   which leaves a tag of at least 48 bits. Other targets use an `AtomicU32`, with a tag of 25
+//!   bits for `N = 64` and at least 16 bits. The protection holds as long as a producer is not
+//!   stalled between reading the head and swapping it while the tag wraps.
+//! - The pending stack, pushed by the producers and taken as a whole by the consumer, which needs
+//!   no tag as nodes are never popped one at a time.
+//! - The sorted chain, which is only touched by the consumer.
+//!
+//! The consumer merges the pending nodes into the sorted chain before it looks at the list.
+//!
+//! The sorted chain is not the `Links` over `Node`s that the other lists share, only the ordering
+//! through `Kind::stays_before` is. `Links` works on the nodes as a `&mut [Node]`, which would
+//! alias the nodes the producers write at the same time, and its `next` and `prev` links are
+//! plain indexes while a node here must keep its link atomic for the free and pending stacks.
+//! A second set of links just for the sorted chain would cost memory in every node, so the
+//! consumer reuses the atomic `next` with relaxed accesses, and keeps to the two operations it
+//! needs: sorted insertion and popping the head.
+
+use core::marker::PhantomData;
+use core::mem::{self, MaybeUninit};
+
+#[cfg(loom)]
+use loom::{
+    cell::UnsafeCell,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+};
+
+#[cfg(not(loom))]
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+#[cfg(all(not(loom), not(target_has_atomic = "64")))]
+use core::sync::atomic::AtomicU32 as AtomicTagged;
+#[cfg(all(not(loom), target_has_atomic = "64"))]
+use core::sync::atomic::AtomicU64 as AtomicTagged;
+#[cfg(all(loom, not(target_has_atomic = "64")))]
+use loom::sync::atomic::AtomicU32 as AtomicTagged;
+#[cfg(all(loom, target_has_atomic = "64"))]
+use loom::sync::atomic::AtomicU64 as AtomicTagged;
+
+use super::{kind, PopError, PushError};
+
+/// The index marking the end of a chain.
+const NONE: u32 = 0xffff;
+
+/// The head of the free stack, an index and an ABA tag in the widest atomic of the target.
+#[cfg(target_has_atomic = "64")]
+type Tagged = u64;
+#[cfg(not(target_has_atomic = "64"))]
+type Tagged = u32;
+
+/// A sorted linked list that any number of producers push to without locking, for example from
+/// several interrupts, and that a single [`MpscConsumer`] takes elements from.
+///
+/// Pushing takes a free node and adds it to a pending chain in `O(1)`. The consumer sorts the
+/// pending elements into the list before [`MpscConsumer::peek`] and [`MpscConsumer::pop`], so
+/// that the pushes are merged in the order they completed. A push fails only when there is no
+/// released node to reuse and all the nodes that were never used are taken, which is when every
+/// node holds an element that is pending, in the list or being popped.
+///
+/// The list uses 16 bit indexes, so `N` can be at most 65 535. It needs atomic compare and swap,
+/// which not all targets have.
+///
+/// On targets without 64 bit atomics, a push that is stalled, for example by higher priority
+/// interrupts, while the consumer releases `2^(32 - ceil(log2(N + 1)))` nodes can corrupt the
+/// free nodes, as the tag that tells released nodes apart wraps. That is over 33 million releases
+/// for `N = 64`. With 64 bit atomics it takes at least `2^48` releases.
+///
+/// # Example
+///
+/// ```
+/// use linked_list::{LinkedListMpsc, Min};
+///
+/// static TIMERS: LinkedListMpsc<u32, Min, 8> = LinkedListMpsc::new();
+///
+/// // Producers, for example interrupts, push through a shared reference
+/// TIMERS.push(30).unwrap();
+/// TIMERS.push(10).unwrap();
+/// TIMERS.push(20).unwrap();
+///
+/// // There is one consumer at a time
+/// let mut consumer = TIMERS.consumer().unwrap();
+/// assert!(TIMERS.consumer().is_none());
+///
+/// assert_eq!(consumer.peek(), Some(&10));
+/// assert_eq!(consumer.pop(), Ok(10));
+///
+/// TIMERS.push(15).unwrap();
+/// assert_eq!(consumer.pop(), Ok(15));
+/// assert_eq!(consumer.len(), 2);
+/// ```
+pub struct LinkedListMpsc<T, Kind, const N: usize> {
+    /// Head of the free stack, tagged above the `INDEX_BITS` lowest bits.
+    free: AtomicTagged,
+    /// Nodes from `fresh` and up have never been used.
+    fresh: AtomicU32,
+    /// Head of the pending stack, the most recent push first.
+    pending: AtomicU32,
+    /// Set while there is an `MpscConsumer`.
+    consumer: AtomicBool,
+    sorted: UnsafeCell<Sorted>,
+    /// Only a marker, so the list is `Send` and `Sync` whatever the kind.
+    _kind: PhantomData<fn() -> Kind>,
+    nodes: [MpscNode<T>; N],
+}
+
+/// The consumer side of a [`LinkedListMpsc`].
+struct Sorted {
+    head: u32,
+    len: usize,
+    /// Pending nodes that are being merged, in the order they were pushed.
+    unmerged: u32,
+}
+
+/// A node in a [`LinkedListMpsc`].
+struct MpscNode<T> {
+    val: UnsafeCell<MaybeUninit<T>>,
+    next: AtomicU32,
+}
+
+// Safety: The list owns its elements, which are handed from the producers to the consumer
+// through the atomic chains. Shared references to the elements are only given to the single
+// consumer, so the elements only need to be `Send`, as for a mutex.
+unsafe impl<T: Send, Kind, const N: usize> Send for LinkedListMpsc<T, Kind, N> {}
+
+// Safety: See above.
+unsafe impl<T: Send, Kind, const N: usize> Sync for LinkedListMpsc<T, Kind, N> {}
+
+#[cfg(not(loom))]
+impl<T, Kind, const N: usize> LinkedListMpsc<T, Kind, N> {
+    /// The indexes `0..N` must be below `NONE`.
+    const CAPACITY_CHECK: () = assert!(N <= NONE as usize, "`N` can be at most 65 535");
+
+    // Only used to initialize the nodes, every use is a new node
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NODE: MpscNode<T> = MpscNode {
+        val: UnsafeCell::new(MaybeUninit::uninit()),
+        next: AtomicU32::new(NONE),
+    };
+
+    /// Create a new empty list.
+    ///
+    /// `N` can be at most 65 535, larger capacities fail to compile.
+    ///
+    /// ```compile_fail,E0080
+    /// use linked_list::{LinkedListMpsc, Max};
+    /// let ll: LinkedListMpsc<u32, Max, 65_536> = LinkedListMpsc::new();
+    /// ```
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY_CHECK;
+
+        LinkedListMpsc {
+            free: AtomicTagged::new(Self::INDEX_MASK),
+            fresh: AtomicU32::new(0),
+            pending: AtomicU32::new(NONE),
+            consumer: AtomicBool::new(false),
+            sorted: UnsafeCell::new(Sorted::EMPTY),
+            _kind: PhantomData,
+            nodes: [Self::NODE; N],
+        }
+    }
+}
+
+#[cfg(loom)]
+impl<T, Kind, const N: usize> LinkedListMpsc<T, Kind, N> {
+    /// Create a new empty list, the loom types can not be created in a `const fn`.
+    pub fn new() -> Self {
+        assert!(N <= NONE as usize, "`N` can be at most 65 535");
+
+        LinkedListMpsc {
+            free: AtomicTagged::new(Self::INDEX_MASK),
+            fresh: AtomicU32::new(0),
+            pending: AtomicU32::new(NONE),
+            consumer: AtomicBool::new(false),
+            sorted: UnsafeCell::new(Sorted::EMPTY),
+            _kind: PhantomData,
+            nodes: core::array::from_fn(|_| MpscNode {
+                val: UnsafeCell::new(MaybeUninit::uninit()),
+                next: AtomicU32::new(NONE),
+            }),
+        }
+    }
+}
+
+impl Sorted {
+    const EMPTY: Self = Sorted {
+        head: NONE,
+        len: 0,
+        unmerged: NONE,
+    };
+}
+
+impl<T, Kind, const N: usize> LinkedListMpsc<T, Kind, N>
+where
+    Kind: kind::Kind<T>,
+{
+    /// Pushes an element to the list, it is sorted into place by the consumer.
+    ///
+    /// This never blocks, and can be called from any number of threads and interrupts at the
+    /// same time. Fails with [`PushError::Full`] if there is no free node, or with
+    /// [`PushError::Incomparable`] as for [`LinkedList::push`](crate::LinkedList::push).
+    ///
+    /// Complexity is `O(1)`, apart from retries when other pushes change the list at the same
+    /// time.
+    pub fn push(&self, value: T) -> Result<(), PushError<T>> {
+        if !Kind::accepts(&value) {
+            return Err(PushError::Incomparable(value));
+        }
+
+        let index = match self.take_free() {
+            Some(index) => index,
+            None => return Err(PushError::Full(value)),
+        };
+
+        let node = self.node(index);
+        // Safety: The node was taken from the free nodes, so no one else accesses its value.
+        node.val
+            .with_mut(|val| unsafe { (*val).as_mut_ptr().write(value) });
+
+        // The release hands the value over to the consumer
+        let mut head = self.pending.load(Ordering::Relaxed);
+        loop {
+            node.next.store(head, Ordering::Relaxed);
+
+            match self.pending.compare_exchange_weak(
+                head,
+                index,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Ok(()),
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Take the consumer side of the list, returns `None` if it is already taken.
+    ///
+    /// It is given back when the [`MpscConsumer`] is dropped.
+    pub fn consumer(&self) -> Option<MpscConsumer<'_, T, Kind, N>> {
+        // The acquire sees the sorted chain as the last consumer left it
+        if self.consumer.swap(true, Ordering::Acquire) {
+            None
+        } else {
+            Some(MpscConsumer { list: self })
+        }
+    }
+}
+
+impl<T, Kind, const N: usize> LinkedListMpsc<T, Kind, N> {
+    /// The bits of the free stack head that hold the index, enough for `0..=N`, the rest is the
+    /// ABA tag.
+    const INDEX_BITS: u32 = usize::BITS - N.leading_zeros();
+
+    /// The index bits of the free stack head, all set for an empty stack.
+    const INDEX_MASK: Tagged = (1 << Self::INDEX_BITS) - 1;
+
+    /// The number of nodes, taken or free.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Internal access helper
+    #[inline(always)]
+    fn node(&self, index: u32) -> &MpscNode<T> {
+        // Safety: Only indexes below `N` are put in the chains.
+        unsafe { self.nodes.get_unchecked(index as usize) }
+    }
+
+    /// The index of the top node in the free stack head, `NONE` if the stack is empty.
+    // `Tagged` is a `u32` on targets without 64 bit atomics
+    #[allow(clippy::unnecessary_cast)]
+    #[inline(always)]
+    fn free_top(head: Tagged) -> u32 {
+        match head & Self::INDEX_MASK {
+            index if index == Self::INDEX_MASK => NONE,
+            index => index as u32,
+        }
+    }
+
+    /// Takes a node from the free stack, or a fresh node if the stack is empty.
+    fn take_free(&self) -> Option<u32> {
+        // The acquire sees the `next` link written before the node was released, and orders
+        // writing the value after the consumer took out the last one
+        let mut head = self.free.load(Ordering::Acquire);
+
+        loop {
+            let index = Self::free_top(head);
+            if index == NONE {
+                break;
+            }
+
+            // The node may be taken by another producer in the meantime, in which case the tag
+            // or index of the head has changed and the exchange fails. `NONE` masks to the empty
+            // stack, as `N` is at most `NONE`.
+            let next = self.node(index).next.load(Ordering::Relaxed);
+
+            match self.free.compare_exchange_weak(
+                head,
+                (head & !Self::INDEX_MASK) | (next as Tagged & Self::INDEX_MASK),
+                Ordering::Acquire,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Some(index),
+                Err(current) => head = current,
+            }
+        }
+
+        self.fresh
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |fresh| {
+                if (fresh as usize) < N {
+                    Some(fresh + 1)
+                } else {
+                    None
+                }
+            })
+            .ok()
+    }
+
+    /// Pushes a node onto the free stack.
+    ///
+    /// Only called by the consumer, which makes it the only one pushing to the stack.
+    fn release(&self, index: u32) {
+        let node = self.node(index);
+        let mut head = self.free.load(Ordering::Relaxed);
+
+        loop {
+            node.next.store(Self::free_top(head), Ordering::Relaxed);
+            let tagged =
+                (head & !Self::INDEX_MASK).wrapping_add(Self::INDEX_MASK + 1) | index as Tagged;
+
+            match self.free.compare_exchange_weak(
+                head,
+                tagged,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+}
+
+impl<T, Kind, const N: usize> Default for LinkedListMpsc<T, Kind, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, Kind, const N: usize> Drop for LinkedListMpsc<T, Kind, N> {
+    fn drop(&mut self) {
+        if !mem::needs_drop::<T>() {
+            return;
+        }
+
+        let (head, unmerged) = self.sorted.with(|sorted| {
+            // Safety: The list is not shared anymore.
+            let sorted = unsafe { &*sorted };
+            (sorted.head, sorted.unmerged)
+        });
+        let pending = self.pending.load(Ordering::Acquire);
+
+        // Every chain except the free stack holds values
+        for mut index in [head, unmerged, pending] {
+            while index != NONE {
+                let node = self.node(index);
+                // Safety: The nodes in these chains hold a value.
+                node.val
+                    .with_mut(|val| unsafe { (*val).as_mut_ptr().drop_in_place() });
+                index = node.next.load(Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// The consumer side of a [`LinkedListMpsc`], from [`LinkedListMpsc::consumer`].
+///
+/// There is at most one consumer of a list at a time. The pending elements are merged into the
+/// list before looking at it, so each operation has a worst-case complexity of `O(N)` for each
+/// pending element.
+pub struct MpscConsumer<'a, T, Kind, const N: usize>
+where
+    Kind: kind::Kind<T>,
+{
+    list: &'a LinkedListMpsc<T, Kind, N>,
+}
+
+impl<T, Kind, const N: usize> MpscConsumer<'_, T, Kind, N>
+where
+    Kind: kind::Kind<T>,
+{
+    /// Peek the first element in the list, after merging the pending elements.
+    pub fn peek(&mut self) -> Option<&T> {
+        self.merge();
+
+        let head = self.sorted(|sorted| sorted.head);
+        // Safety: The nodes in the sorted chain hold a value.
+        if head == NONE {
+            None
+        } else {
+            Some(unsafe { self.value(head) })
+        }
+    }
+
+    /// Pops the first element in the list, after merging the pending elements.
+    pub fn pop(&mut self) -> Result<T, PopError> {
+        self.merge();
+
+        let head = self.sorted(|sorted| sorted.head);
+        if head == NONE {
+            return Err(PopError);
+        }
+
+        let next = self.list.node(head).next.load(Ordering::Relaxed);
+        self.sorted(|sorted| {
+            sorted.head = next;
+            sorted.len -= 1;
+        });
+
+        Ok(unsafe { self.take(head) })
+    }
+
+    /// The number of elements in the list, after merging the pending elements.
+    pub fn len(&mut self) -> usize {
+        self.merge();
+        self.sorted(|sorted| sorted.len)
+    }
+
+    /// Checks if the list is empty, after merging the pending elements.
+    pub fn is_empty(&mut self) -> bool {
+        self.len() == 0
+    }
+
+    /// Internal access helper
+    #[inline(always)]
+    fn sorted<R>(&mut self, f: impl FnOnce(&mut Sorted) -> R) -> R {
+        // Safety: Only the consumer accesses the sorted chain.
+        self.list
+            .sorted
+            .with_mut(|sorted| f(unsafe { &mut *sorted }))
+    }
+
+    /// Internal access helper
+    ///
+    /// # Safety
+    ///
+    /// The node must hold a value.
+    #[inline(always)]
+    unsafe fn value(&self, index: u32) -> &T {
+        self.list.node(index).val.with(|val| &*(*val).as_ptr())
+    }
+
+    /// Takes the value out of a node that is not in any chain and releases the node.
+    ///
+    /// # Safety
+    ///
+    /// The node must hold a value.
+    unsafe fn take(&mut self, index: u32) -> T {
+        let value = self.list.node(index).val.with(|val| (*val).as_ptr().read());
+        self.list.release(index);
+        value
+    }
+
+    /// Sorts the pending elements into the list.
+    fn merge(&mut self) {
+        // Finish a merge interrupted by a panicking comparison first
+        self.merge_unmerged();
+
+        // The acquire sees the values the producers wrote before they pushed
+        let mut pending = self.list.pending.swap(NONE, Ordering::Acquire);
+        if pending == NONE {
+            return;
+        }
+
+        // The pending stack has the most recent push first, reverse it into push order
+        let mut unmerged = NONE;
+        while pending != NONE {
+            let node = self.list.node(pending);
+            let next = node.next.load(Ordering::Relaxed);
+            node.next.store(unmerged, Ordering::Relaxed);
+            unmerged = pending;
+            pending = next;
+        }
+
+        self.sorted(|sorted| sorted.unmerged = unmerged);
+        self.merge_unmerged();
+    }
+
+    /// Sorts the unmerged elements into the list.
+    fn merge_unmerged(&mut self) {
+        loop {
+            let index = self.sorted(|sorted| sorted.unmerged);
+            if index == NONE {
+                return;
+            }
+
+            let node = self.list.node(index);
+            let next = node.next.load(Ordering::Relaxed);
+            self.sorted(|sorted| sorted.unmerged = next);
+
+            // If a comparison panics, the element is dropped and its node released
+            let guard = TakeGuard {
+                consumer: self,
+                index,
+            };
+            let prev = guard.consumer.search(index);
+            let consumer = guard.defuse();
+
+            let next = match prev {
+                NONE => consumer.sorted(|sorted| sorted.head),
+                prev => consumer.list.node(prev).next.load(Ordering::Relaxed),
+            };
+
+            node.next.store(next, Ordering::Relaxed);
+            match prev {
+                NONE => consumer.sorted(|sorted| sorted.head = index),
+                prev => consumer
+                    .list
+                    .node(prev)
+                    .next
+                    .store(index, Ordering::Relaxed),
+            }
+            consumer.sorted(|sorted| sorted.len += 1);
+        }
+    }
+
+    /// Finds the node the element at `index` is linked after, `NONE` for the head.
+    fn search(&mut self, index: u32) -> u32 {
+        let head = self.sorted(|sorted| sorted.head);
+        let mut prev = NONE;
+        let mut current = head;
+
+        // Safety: The element being merged and the nodes in the sorted chain hold a value.
+        unsafe {
+            let value = self.value(index);
+
+            while current != NONE && Kind::stays_before(self.value(current), value) {
+                prev = current;
+                current = self.list.node(current).next.load(Ordering::Relaxed);
+            }
+        }
+
+        prev
+    }
+}
+
+impl<T, Kind, const N: usize> Drop for MpscConsumer<'_, T, Kind, N>
+where
+    Kind: kind::Kind<T>,
+{
+    fn drop(&mut self) {
+        // The release hands the sorted chain over to the next consumer
+        self.list.consumer.store(false, Ordering::Release);
+    }
+}
+
+/// Drops the element of a node that is not in any chain, unless defused.
+struct TakeGuard<'c, 'a, T, Kind, const N: usize>
+where
+    Kind: kind::Kind<T>,
+{
+    consumer: &'c mut MpscConsumer<'a, T, Kind, N>,
+    index: u32,
+}
+
+impl<'c, 'a, T, Kind, const N: usize> TakeGuard<'c, 'a, T, Kind, N>
+where
+    Kind: kind::Kind<T>,
+{
+    fn defuse(self) -> &'c mut MpscConsumer<'a, T, Kind, N> {
+        let this = mem::ManuallyDrop::new(self);
+        // Safety: `this` is not dropped, so the reference is moved out once.
+        unsafe { core::ptr::read(&this.consumer) }
+    }
+}
+
+impl<T, Kind, const N: usize> Drop for TakeGuard<'_, '_, T, Kind, N>
+where
+    Kind: kind::Kind<T>,
+{
+    fn drop(&mut self) {
+        // Safety: The node being merged holds a value and is not in any chain.
+        drop(unsafe { self.consumer.take(self.index) });
+    }
+}
+
+#[cfg(not(loom))]
+mod cell {
+    /// An `UnsafeCell` with the closure based API of loom, which checks the accesses.
+    pub(super) struct UnsafeCell<T>(core::cell::UnsafeCell<T>);
+
+    impl<T> UnsafeCell<T> {
+        pub(super) const fn new(value: T) -> Self {
+            UnsafeCell(core::cell::UnsafeCell::new(value))
+        }
+
+        #[inline(always)]
+        pub(super) fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+            f(self.0.get())
+        }
+
+        #[inline(always)]
+        pub(super) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+            f(self.0.get())
+        }
+    }
+}
+
+#[cfg(not(loom))]
+use cell::UnsafeCell;
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+    use crate::test_util::{Bomb, Prio};
+    use crate::{MaxStable, Min};
+
+    #[test]
+    fn test_mpsc() {
+        let ll: LinkedListMpsc<u32, Min, 4> = LinkedListMpsc::new();
+        let mut consumer = ll.consumer().unwrap();
+        assert_eq!(consumer.pop(), Err(PopError));
+
+        for v in [3, 1, 4, 2] {
+            ll.push(v).unwrap();
+        }
+        assert_eq!(ll.push(5), Err(PushError::Full(5)));
+
+        assert_eq!(consumer.len(), 4);
+        assert_eq!(consumer.pop(), Ok(1));
+
+        // Released nodes are reused
+        ll.push(0).unwrap();
+        assert_eq!(ll.push(5), Err(PushError::Full(5)));
+
+        let values: Vec<_> = core::iter::from_fn(|| consumer.pop().ok()).collect();
+        assert_eq!(values, [0, 2, 3, 4]);
+        assert!(consumer.is_empty());
+
+        // The consumer is given back when dropped
+        assert!(ll.consumer().is_none());
+        drop(consumer);
+        assert!(ll.consumer().is_some());
+    }
+
+    #[test]
+    fn test_mpsc_stable() {
+        let ll: LinkedListMpsc<Prio, MaxStable, 8> = LinkedListMpsc::new();
+        let mut consumer = ll.consumer().unwrap();
+
+        ll.push(Prio(1, 'a')).unwrap();
+        ll.push(Prio(2, 'b')).unwrap();
+        assert_eq!(consumer.peek().map(|p| (p.0, p.1)), Some((2, 'b')));
+
+        // Equal elements are merged in push order
+        ll.push(Prio(1, 'c')).unwrap();
+        ll.push(Prio(2, 'd')).unwrap();
+        ll.push(Prio(1, 'e')).unwrap();
+
+        let tags: String = core::iter::from_fn(|| consumer.pop().ok())
+            .map(|v| v.1)
+            .collect();
+        assert_eq!(tags, "bdace");
+    }
+
+    #[test]
+    fn test_mpsc_tag_wrap() {
+        type List = LinkedListMpsc<u32, Min, 64>;
+
+        // The indexes `0..=64` take 7 bits, the rest of the head is the tag
+        assert_eq!(List::INDEX_MASK, 0x7f);
+
+        let ll: List = LinkedListMpsc::new();
+        let mut consumer = ll.consumer().unwrap();
+        ll.push(1).unwrap();
+        ll.push(2).unwrap();
+        assert_eq!(consumer.pop(), Ok(1));
+
+        // Move the tag to the last value before it wraps, as seen by a stalled producer
+        let head = ll.free.load(Ordering::Relaxed);
+        let stale = !List::INDEX_MASK | (head & List::INDEX_MASK);
+        ll.free.store(stale, Ordering::Relaxed);
+
+        // Taking and releasing the same node wraps the tag
+        ll.push(0).unwrap();
+        assert_eq!(consumer.pop(), Ok(0));
+
+        let head = ll.free.load(Ordering::Relaxed);
+        assert_eq!(List::free_top(head), List::free_top(stale));
+        assert_eq!(head & !List::INDEX_MASK, 0);
+        assert!(ll
+            .free
+            .compare_exchange(
+                stale,
+                List::INDEX_MASK,
+                Ordering::Relaxed,
+                Ordering::Relaxed
+            )
+            .is_err());
+
+        // No nodes were lost or handed out twice
+        for v in 3..66 {
+            ll.push(v).unwrap();
+        }
+        assert_eq!(ll.push(66), Err(PushError::Full(66)));
+
+        let values: Vec<_> = core::iter::from_fn(|| consumer.pop().ok()).collect();
+        assert!(values.into_iter().eq(2..66));
+    }
+
+    #[test]
+    fn test_mpsc_threads() {
+        let ll: LinkedListMpsc<u32, Min, 64> = LinkedListMpsc::new();
+        let mut popped = Vec::new();
+
+        std::thread::scope(|s| {
+            for t in 0..4 {
+                let ll = &ll;
+                s.spawn(move || {
+                    for v in 0..100 {
+                        // Retry while the consumer frees nodes
+                        while ll.push(t * 100 + v).is_err() {
+                            std::thread::yield_now();
+                        }
+                    }
+                });
+            }
+
+            let mut consumer = ll.consumer().unwrap();
+            while popped.len() < 400 {
+                match consumer.pop() {
+                    Ok(v) => popped.push(v),
+                    Err(PopError) => std::thread::yield_now(),
+                }
+            }
+        });
+
+        popped.sort_unstable();
+        assert!(popped.into_iter().eq(0..400));
+    }
+
+    #[test]
+    fn test_mpsc_drop() {
+        use std::rc::Rc;
+
+        let value = Rc::new(());
+
+        {
+            let ll: LinkedListMpsc<(u32, Rc<()>), Min, 8> = LinkedListMpsc::new();
+            for v in 0..6 {
+                ll.push((v, value.clone())).unwrap();
+            }
+
+            // Some elements are merged, the rest are pending
+            let mut consumer = ll.consumer().unwrap();
+            assert_eq!(consumer.pop().map(|v| v.0), Ok(0));
+            assert_eq!(Rc::strong_count(&value), 1 + 5);
+            ll.push((9, value.clone())).unwrap();
+        }
+
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_mpsc_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let ll: LinkedListMpsc<Bomb, Min, 4> = LinkedListMpsc::new();
+        let mut consumer = ll.consumer().unwrap();
+        ll.push(Bomb::new(2)).unwrap();
+        ll.push(Bomb::new(0)).unwrap();
+        ll.push(Bomb::new(1)).unwrap();
+
+        // The bomb is dropped and the rest of the pending elements are merged later
+        let r = catch_unwind(AssertUnwindSafe(|| consumer.peek().map(|b| b.0)));
+        assert!(r.is_err());
+        assert_eq!(consumer.pop().map(|b| b.0), Ok(1));
+        assert_eq!(consumer.pop().map(|b| b.0), Ok(2));
+        assert!(consumer.is_empty());
+
+        // No nodes were lost
+        for v in 1..5 {
+            ll.push(Bomb::new(v)).unwrap();
+        }
+        assert!(ll.push(Bomb::new(5)).is_err());
+    }
+
+    #[test]
+    fn test_mpsc_send_sync() {
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+
+        assert_send::<LinkedListMpsc<u32, Min, 4>>();
+        assert_sync::<LinkedListMpsc<u32, Min, 4>>();
+        assert_sync::<LinkedListMpsc<core::cell::Cell<u32>, Min, 4>>();
+        assert_send::<MpscConsumer<'static, u32, Min, 4>>();
+    }
+}
+
+/// Run with `RUSTFLAGS="--cfg loom" cargo test --release --features mpsc --lib mpsc`.
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use super::*;
+    use crate::Min;
+    use loom::sync::Arc;
+    use loom::thread;
+
+    #[test]
+    fn producers_and_consumer() {
+        loom::model(|| {
+            let ll: Arc<LinkedListMpsc<u32, Min, 2>> = Arc::new(LinkedListMpsc::new());
+
+            let producers: Vec<_> = (0..2)
+                .map(|v| {
+                    let ll = ll.clone();
+                    thread::spawn(move || ll.push(v).unwrap())
+                })
+                .collect();
+
+            let mut consumer = ll.consumer().unwrap();
+            let first = consumer.pop().ok();
+
+            for producer in producers {
+                producer.join().unwrap();
+            }
+
+            // Everything pushed is popped exactly once, and the rest in order
+            let rest: Vec<_> = core::iter::from_fn(|| consumer.pop().ok()).collect();
+            let mut all: Vec<_> = first.into_iter().chain(rest.iter().copied()).collect();
+            assert!(rest.windows(2).all(|w| w[0] <= w[1]));
+            all.sort_unstable();
+            assert_eq!(all, [0, 1]);
+        });
+    }
+
+    #[test]
+    fn reuse_released_nodes() {
+        loom::model(|| {
+            let ll: Arc<LinkedListMpsc<u32, Min, 2>> = Arc::new(LinkedListMpsc::new());
+            ll.push(0).unwrap();
+            ll.push(1).unwrap();
+
+            // Producers race for the nodes the consumer releases
+            let producers: Vec<_> = (2..4)
+                .map(|v| {
+                    let ll = ll.clone();
+                    thread::spawn(move || ll.push(v).is_ok())
+                })
+                .collect();
+
+            let mut consumer = ll.consumer().unwrap();
+            let mut popped = vec![consumer.pop().unwrap()];
+            popped.push(consumer.pop().unwrap());
+
+            let pushed = producers
+                .into_iter()
+                .map(|producer| producer.join().unwrap())
+                .filter(|&ok| ok)
+                .count();
+
+            popped.extend(core::iter::from_fn(|| consumer.pop().ok()));
+            assert_eq!(popped.len(), 2 + pushed);
+            assert_eq!(popped[..2], [0, 1]);
+        });
+    }
+}
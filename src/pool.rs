@@ -0,0 +1,732 @@
+//! A node pool shared by several sorted lists.
+//!
+//! Every list in the pool is a chain of nodes with its own head and tail, while the free nodes
+//! are shared. An element can move to another list by relinking its node, so the value is never
+//! moved and its handle stays valid.
+
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+
+use super::{
+    kind, FreeList, Handle, Iter, LinkedListIndex, Links, Node, PopError, Position, PushError,
+    StaleHandle,
+};
+
+/// A pool of `N` nodes shared by `L` sorted lists.
+///
+/// Lists are selected by their number in `0..L`, and methods taking a list number panic if it is
+/// out of range.
+///
+/// All lists share the same `Kind`. A kind is a type while the lists are picked by a number at
+/// runtime, so a kind per list would make each list a type of its own, and
+/// [`move_to`](LinkedListPool::move_to) could not name the kind of its target. Lists that need
+/// different orderings need a pool each.
+///
+/// # Example
+///
+/// ```
+/// use linked_list::{LinkedIndexU8, LinkedListPool, Min};
+///
+/// // Two priority bands sharing 8 nodes
+/// let mut pool: LinkedListPool<u32, LinkedIndexU8, Min, 8, 2> = LinkedListPool::new();
+///
+/// let handle = pool.push(1, 30).unwrap();
+/// pool.push(1, 10).unwrap();
+/// pool.push(0, 20).unwrap();
+///
+/// // Promote an element to the first band, the handle stays valid
+/// pool.move_to(handle, 0).unwrap();
+///
+/// assert!(pool.iter(0).eq(&[20, 30]));
+/// assert!(pool.iter(1).eq(&[10]));
+/// assert_eq!(pool.get(handle), Ok(&30));
+/// ```
+pub struct LinkedListPool<T, Idx, Kind, const N: usize, const L: usize>
+where
+    Idx: LinkedListIndex,
+{
+    lists: [Links<Idx>; L],
+    free: FreeList<Idx>,
+    /// The list each occupied node is linked into.
+    owners: [u8; N],
+    _kind: PhantomData<Kind>,
+    nodes: [Node<T, Idx>; N],
+}
+
+// Safety: As for the list, the pool owns its elements and `Kind` is never instantiated.
+unsafe impl<T, Idx, Kind, const N: usize, const L: usize> Send
+    for LinkedListPool<T, Idx, Kind, N, L>
+where
+    T: Send,
+    Idx: LinkedListIndex + Send,
+{
+}
+
+// Safety: A shared pool only hands out shared references to its elements, and has no interior
+// mutability.
+unsafe impl<T, Idx, Kind, const N: usize, const L: usize> Sync
+    for LinkedListPool<T, Idx, Kind, N, L>
+where
+    T: Sync,
+    Idx: LinkedListIndex + Sync,
+{
+}
+
+impl<T, Idx, Kind, const N: usize, const L: usize> LinkedListPool<T, Idx, Kind, N, L>
+where
+    Idx: LinkedListIndex,
+{
+    /// The indexes `0..N` must be below `Idx::NONE`, and the list numbers must fit the owners.
+    const CAPACITY_CHECK: () = {
+        assert!(N <= Idx::MAX_LEN, "`N` is too large for the index type");
+        assert!(L <= 256, "`L` can be at most 256");
+    };
+
+    /// Create a new pool with `L` empty lists.
+    ///
+    /// `N` can be at most the maximum value of the index type and `L` can be at most 256, larger
+    /// values fail to compile.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedIndexU16, LinkedListPool, Max};
+    /// static mut POOL: LinkedListPool<u32, LinkedIndexU16, Max, 1000, 4> = LinkedListPool::new();
+    /// ```
+    ///
+    /// ```compile_fail,E0080
+    /// use linked_list::{LinkedIndexU8, LinkedListPool, Max};
+    /// let pool: LinkedListPool<u32, LinkedIndexU8, Max, 8, 257> = LinkedListPool::new();
+    /// ```
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY_CHECK;
+
+        LinkedListPool {
+            lists: [Links::EMPTY; L],
+            free: FreeList::EMPTY,
+            owners: [0; N],
+            _kind: PhantomData,
+            nodes: [Node::UNINIT; N],
+        }
+    }
+}
+
+impl<T, Idx, Kind, const N: usize, const L: usize> LinkedListPool<T, Idx, Kind, N, L>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+{
+    /// Checks that a handle points to an element in the pool and returns its index.
+    #[inline]
+    fn check_handle(&self, handle: Handle<Idx>) -> Result<usize, StaleHandle> {
+        match handle.index.option() {
+            Some(index) if index < N && self.nodes[index].generation == handle.generation => {
+                Ok(index)
+            }
+            _ => Err(StaleHandle),
+        }
+    }
+
+    /// Unlinks the node at `index` from the list it is in.
+    ///
+    /// # Safety
+    ///
+    /// The node at `index` must be linked into a list.
+    #[inline]
+    unsafe fn unlink(&mut self, index: usize) {
+        let list = usize::from(self.owners[index]);
+        self.lists[list].unlink(&mut self.nodes, index);
+    }
+
+    /// Finds the sorted position of `value` in `list`.
+    #[inline]
+    fn search(&self, list: usize, value: &T) -> Position<Idx> {
+        // Safety: All nodes linked into the lists are in `self.nodes`.
+        unsafe { self.lists[list].search::<Kind, _>(&self.nodes, value) }
+    }
+
+    /// Links the node at `index` into `list` at the position found by `search`.
+    ///
+    /// # Safety
+    ///
+    /// The node at `index` must hold a value and must not be linked into any list, and `list`
+    /// must not have changed since the position was found.
+    #[inline]
+    unsafe fn link_at(&mut self, list: usize, index: usize, at: &Position<Idx>) {
+        self.lists[list].link_at(&mut self.nodes, index, at);
+        // `L <= 256` is checked when the pool is created
+        self.owners[index] = list as u8;
+    }
+
+    /// Pushes an element to a list and sorts it into place, fails if all nodes in the pool are
+    /// taken or if the kind rejects the element, see [`LinkedList::push`](crate::LinkedList::push).
+    ///
+    /// Complexity is worst-case `O(N)`, the lists in a pool have no express lanes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedIndexU8, LinkedListPool, Min, PushError};
+    /// let mut pool: LinkedListPool<u32, LinkedIndexU8, Min, 2, 2> = LinkedListPool::new();
+    ///
+    /// pool.push(0, 2).unwrap();
+    /// pool.push(1, 1).unwrap();
+    ///
+    /// // The nodes are shared, so the pool is full for every list
+    /// assert_eq!(pool.push(0, 3), Err(PushError::Full(3)));
+    /// assert_eq!(pool.push(1, 3), Err(PushError::Full(3)));
+    /// ```
+    pub fn push(&mut self, list: usize, value: T) -> Result<Handle<Idx>, PushError<T>> {
+        assert!(list < L, "list out of range");
+
+        if !Kind::accepts(&value) {
+            return Err(PushError::Incomparable(value));
+        }
+
+        if self.is_full() {
+            return Err(PushError::Full(value));
+        }
+
+        // Find the position first, so a panicking comparison leaves the pool as it was
+        let position = self.search(list, &value);
+
+        unsafe {
+            let (index, generation) = self.free.occupy(&mut self.nodes, value);
+            self.link_at(list, index, &position);
+
+            Ok(Handle {
+                index: Idx::new_unchecked(index),
+                generation,
+            })
+        }
+    }
+
+    /// Peek the first element of a list.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    pub fn peek(&self, list: usize) -> Option<&T> {
+        self.lists[list]
+            .head
+            .option()
+            .map(|head| unsafe { self.nodes[head].value() })
+    }
+
+    /// Pop the first element of a list.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedIndexU8, LinkedListPool, Min, PopError};
+    /// let mut pool: LinkedListPool<u32, LinkedIndexU8, Min, 4, 2> = LinkedListPool::new();
+    ///
+    /// pool.push(0, 2).unwrap();
+    /// pool.push(0, 1).unwrap();
+    ///
+    /// assert_eq!(pool.pop(0), Ok(1));
+    /// assert_eq!(pool.pop(0), Ok(2));
+    /// assert_eq!(pool.pop(0), Err(PopError));
+    /// assert_eq!(pool.pop(1), Err(PopError));
+    /// ```
+    pub fn pop(&mut self, list: usize) -> Result<T, PopError> {
+        let head = self.lists[list].head.option().ok_or(PopError)?;
+
+        unsafe {
+            self.unlink(head);
+            Ok(self.free.release(&mut self.nodes, head))
+        }
+    }
+
+    /// Pop the last element of a list.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedIndexU8, LinkedListPool, Min, PopError};
+    /// let mut pool: LinkedListPool<u32, LinkedIndexU8, Min, 4, 2> = LinkedListPool::new();
+    ///
+    /// pool.push(0, 2).unwrap();
+    /// pool.push(0, 1).unwrap();
+    ///
+    /// assert_eq!(pool.pop_back(0), Ok(2));
+    /// assert_eq!(pool.pop_back(0), Ok(1));
+    /// assert_eq!(pool.pop_back(0), Err(PopError));
+    /// ```
+    pub fn pop_back(&mut self, list: usize) -> Result<T, PopError> {
+        let tail = self.lists[list].tail.option().ok_or(PopError)?;
+
+        unsafe {
+            self.unlink(tail);
+            Ok(self.free.release(&mut self.nodes, tail))
+        }
+    }
+
+    /// Get an iterator over the elements of a list in sorted order.
+    pub fn iter(&self, list: usize) -> Iter<'_, T, Idx, Kind> {
+        Iter {
+            nodes: &self.nodes,
+            index: self.lists[list].head,
+            _kind: PhantomData,
+        }
+    }
+
+    /// Checks if the element a handle points to is still in the pool.
+    #[inline]
+    pub fn contains(&self, handle: Handle<Idx>) -> bool {
+        self.check_handle(handle).is_ok()
+    }
+
+    /// Get a reference to the element a handle points to.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    pub fn get(&self, handle: Handle<Idx>) -> Result<&T, StaleHandle> {
+        let index = self.check_handle(handle)?;
+
+        Ok(unsafe { self.nodes[index].value() })
+    }
+
+    /// Get the element a handle points to so it can be changed and resorted in its list, without
+    /// searching for it.
+    ///
+    /// Complexity is worst-case `O(1)`, resorting is worst-case `O(N)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedIndexU8, LinkedListPool, Min};
+    /// let mut pool: LinkedListPool<u32, LinkedIndexU8, Min, 4, 2> = LinkedListPool::new();
+    ///
+    /// let handle = pool.push(1, 1).unwrap();
+    /// pool.push(1, 2).unwrap();
+    ///
+    /// // Update the value, the handle stays valid after the resort
+    /// let mut update = pool.update(handle).unwrap();
+    /// *update += 1000;
+    /// update.finish();
+    ///
+    /// assert!(pool.iter(1).eq(&[2, 1001]));
+    /// assert_eq!(pool.get(handle), Ok(&1001));
+    /// ```
+    pub fn update(
+        &mut self,
+        handle: Handle<Idx>,
+    ) -> Result<PoolFindMut<'_, T, Idx, Kind, N, L>, StaleHandle> {
+        let index = self.check_handle(handle)?;
+
+        Ok(PoolFindMut {
+            pool: self,
+            index,
+            maybe_changed: false,
+        })
+    }
+
+    /// Find an element in a list that can be changed and resorted in the list.
+    ///
+    /// Complexity is worst-case `O(N)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedIndexU8, LinkedListPool, Min};
+    /// let mut pool: LinkedListPool<u32, LinkedIndexU8, Min, 4, 2> = LinkedListPool::new();
+    ///
+    /// pool.push(0, 1).unwrap();
+    /// pool.push(0, 2).unwrap();
+    /// pool.push(1, 3).unwrap();
+    ///
+    /// let mut find = pool.find_mut(0, |v| *v == 1).unwrap();
+    /// *find += 1000;
+    /// find.finish();
+    ///
+    /// assert!(pool.iter(0).eq(&[2, 1001]));
+    /// assert!(pool.find_mut(0, |v| *v == 3).is_none());
+    /// ```
+    pub fn find_mut<F>(
+        &mut self,
+        list: usize,
+        mut f: F,
+    ) -> Option<PoolFindMut<'_, T, Idx, Kind, N, L>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut index = self.lists[list].head;
+
+        while let Some(current) = index.option() {
+            if f(unsafe { self.nodes[current].value() }) {
+                return Some(PoolFindMut {
+                    pool: self,
+                    index: current,
+                    maybe_changed: false,
+                });
+            }
+
+            index = self.nodes[current].next;
+        }
+
+        None
+    }
+
+    /// Get the list the element a handle points to is in.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    pub fn list_of(&self, handle: Handle<Idx>) -> Result<usize, StaleHandle> {
+        let index = self.check_handle(handle)?;
+
+        Ok(usize::from(self.owners[index]))
+    }
+
+    /// Removes the element a handle points to from its list.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedIndexU8, LinkedListPool, Min, StaleHandle};
+    /// let mut pool: LinkedListPool<u32, LinkedIndexU8, Min, 4, 2> = LinkedListPool::new();
+    ///
+    /// let handle = pool.push(1, 2).unwrap();
+    ///
+    /// assert_eq!(pool.remove(handle), Ok(2));
+    /// assert_eq!(pool.remove(handle), Err(StaleHandle));
+    /// assert!(pool.is_empty(1));
+    /// ```
+    pub fn remove(&mut self, handle: Handle<Idx>) -> Result<T, StaleHandle> {
+        let index = self.check_handle(handle)?;
+
+        unsafe {
+            self.unlink(index);
+            Ok(self.free.release(&mut self.nodes, index))
+        }
+    }
+
+    /// Moves the element a handle points to into another list, without moving the value.
+    ///
+    /// The handle stays valid. Complexity is worst-case `O(N)`: unlinking the element is `O(1)`,
+    /// but it is sorted into `list` by a search from its head.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedIndexU8, LinkedListPool, Min};
+    /// let mut pool: LinkedListPool<u32, LinkedIndexU8, Min, 4, 2> = LinkedListPool::new();
+    ///
+    /// let handle = pool.push(0, 2).unwrap();
+    /// pool.push(1, 1).unwrap();
+    /// pool.push(1, 3).unwrap();
+    ///
+    /// pool.move_to(handle, 1).unwrap();
+    ///
+    /// assert_eq!(pool.list_of(handle), Ok(1));
+    /// assert!(pool.iter(1).eq(&[1, 2, 3]));
+    /// assert!(pool.is_empty(0));
+    /// ```
+    pub fn move_to(&mut self, handle: Handle<Idx>, list: usize) -> Result<(), StaleHandle> {
+        assert!(list < L, "list out of range");
+        let index = self.check_handle(handle)?;
+
+        // The element is already sorted into its own list
+        if usize::from(self.owners[index]) == list {
+            return Ok(());
+        }
+
+        // The node is not in `list`, so its position is found before it is moved and a
+        // panicking comparison leaves the pool as it was
+        let position = self.search(list, unsafe { self.nodes[index].value() });
+
+        unsafe {
+            self.unlink(index);
+            self.link_at(list, index, &position);
+        }
+
+        Ok(())
+    }
+
+    /// Get the number of elements in a list.
+    #[inline]
+    pub fn len(&self, list: usize) -> usize {
+        self.lists[list].len
+    }
+
+    /// Checks if a list is empty.
+    #[inline]
+    pub fn is_empty(&self, list: usize) -> bool {
+        self.lists[list].head.option().is_none()
+    }
+
+    /// Get the number of nodes in the pool.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Checks if all nodes in the pool are taken.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.free.head.option().is_none() && self.free.fresh == N
+    }
+}
+
+impl<T, Idx, Kind, const N: usize, const L: usize> Default for LinkedListPool<T, Idx, Kind, N, L>
+where
+    Idx: LinkedListIndex,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Comes from [`LinkedListPool::find_mut`] and [`LinkedListPool::update`].
+///
+/// The element is resorted in its list when this is dropped, if it was accessed mutably. If a
+/// comparison panics while resorting, the element is removed from the pool and dropped.
+pub struct PoolFindMut<'a, T, Idx, Kind, const N: usize, const L: usize>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+{
+    pool: &'a mut LinkedListPool<T, Idx, Kind, N, L>,
+    index: usize,
+    maybe_changed: bool,
+}
+
+impl<T, Idx, Kind, const N: usize, const L: usize> PoolFindMut<'_, T, Idx, Kind, N, L>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+{
+    /// This will pop the element from its list.
+    ///
+    /// Complexity is worst-case `O(1)`.
+    #[inline]
+    pub fn pop(mut self) -> T {
+        // The element is gone, there is nothing to resort
+        self.maybe_changed = false;
+
+        unsafe {
+            self.pool.unlink(self.index);
+            self.pool.free.release(&mut self.pool.nodes, self.index)
+        }
+    }
+
+    /// This will resort the element into the correct position in its list if needed. The
+    /// resorting will only happen if the element has been accessed mutably.
+    ///
+    /// Same as calling `drop`.
+    ///
+    /// Complexity is worst-case `O(N)`.
+    #[inline]
+    pub fn finish(self) {
+        drop(self)
+    }
+}
+
+impl<T, Idx, Kind, const N: usize, const L: usize> Drop for PoolFindMut<'_, T, Idx, Kind, N, L>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+{
+    fn drop(&mut self) {
+        // Only resort the list if the element has changed, the element keeps its node and list
+        // so handles to it stay valid
+        if self.maybe_changed {
+            let index = self.index;
+            let list = usize::from(self.pool.owners[index]);
+
+            unsafe {
+                self.pool.unlink(index);
+
+                // If a comparison panics the position of the element is unknown, so it is removed
+                // from the pool and dropped
+                let guard = ReleaseGuard {
+                    pool: &mut *self.pool,
+                    index,
+                };
+                let position = guard.pool.search(list, guard.pool.nodes[index].value());
+                mem::forget(guard);
+
+                self.pool.link_at(list, index, &position);
+            }
+        }
+    }
+}
+
+impl<T, Idx, Kind, const N: usize, const L: usize> Deref for PoolFindMut<'_, T, Idx, Kind, N, L>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.pool.nodes[self.index].value() }
+    }
+}
+
+impl<T, Idx, Kind, const N: usize, const L: usize> DerefMut for PoolFindMut<'_, T, Idx, Kind, N, L>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.maybe_changed = true;
+
+        unsafe { self.pool.nodes[self.index].value_mut() }
+    }
+}
+
+/// Releases an unlinked node when dropped.
+struct ReleaseGuard<'a, T, Idx, Kind, const N: usize, const L: usize>
+where
+    Idx: LinkedListIndex,
+{
+    pool: &'a mut LinkedListPool<T, Idx, Kind, N, L>,
+    index: usize,
+}
+
+impl<T, Idx, Kind, const N: usize, const L: usize> Drop for ReleaseGuard<'_, T, Idx, Kind, N, L>
+where
+    Idx: LinkedListIndex,
+{
+    fn drop(&mut self) {
+        // Safety: The guard is only created for an unlinked node holding a value.
+        unsafe { drop(self.pool.free.release(&mut self.pool.nodes, self.index)) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{Bomb, Prio};
+    use crate::{LinkedIndexU8, Max, MinStable};
+
+    #[test]
+    fn test_pool() {
+        let mut pool: LinkedListPool<u32, LinkedIndexU8, Max, 6, 3> = LinkedListPool::new();
+        let mut handles = Vec::new();
+
+        for (i, v) in [5, 1, 4, 2, 6, 3].iter().enumerate() {
+            handles.push(pool.push(i % 3, *v).unwrap());
+        }
+        assert!(pool.is_full());
+        assert_eq!(pool.push(0, 7), Err(PushError::Full(7)));
+
+        assert!(pool.iter(0).eq(&[5, 2]));
+        assert!(pool.iter(1).eq(&[6, 1]));
+        assert!(pool.iter(2).eq(&[4, 3]));
+
+        // Move every element into the last list
+        for handle in &handles {
+            pool.move_to(*handle, 2).unwrap();
+        }
+        assert!(pool.iter(2).eq(&[6, 5, 4, 3, 2, 1]));
+        assert_eq!((pool.len(0), pool.len(1), pool.len(2)), (0, 0, 6));
+        assert_eq!(pool.peek(0), None);
+
+        // Released nodes are shared by all lists
+        assert_eq!(pool.pop(2), Ok(6));
+        assert_eq!(pool.remove(handles[1]), Ok(1));
+        assert_eq!(pool.remove(handles[1]), Err(StaleHandle));
+        assert_eq!(pool.move_to(handles[1], 0), Err(StaleHandle));
+        pool.push(0, 8).unwrap();
+        pool.push(1, 9).unwrap();
+        assert!(pool.is_full());
+
+        assert_eq!(pool.list_of(handles[0]), Ok(2));
+        assert_eq!(pool.peek(0), Some(&8));
+        assert_eq!(pool.peek(1), Some(&9));
+        assert!(pool.iter(2).eq(&[5, 4, 3, 2]));
+    }
+
+    #[test]
+    fn test_pool_stable_move() {
+        let mut pool: LinkedListPool<Prio, LinkedIndexU8, MinStable, 4, 2> = LinkedListPool::new();
+
+        pool.push(1, Prio(1, 'a')).unwrap();
+        let b = pool.push(0, Prio(1, 'b')).unwrap();
+        pool.push(1, Prio(1, 'c')).unwrap();
+
+        // A moved element is placed after its equals
+        pool.move_to(b, 1).unwrap();
+        let tags: Vec<_> = pool.iter(1).map(|p| p.1).collect();
+        assert_eq!(tags, ['a', 'c', 'b']);
+    }
+
+    #[test]
+    fn test_pool_update() {
+        let mut pool: LinkedListPool<u32, LinkedIndexU8, Max, 6, 2> = LinkedListPool::new();
+        let handle = pool.push(0, 1).unwrap();
+        pool.push(0, 2).unwrap();
+        pool.push(0, 3).unwrap();
+        pool.push(1, 4).unwrap();
+
+        // The element is resorted within its own list
+        *pool.update(handle).unwrap() += 10;
+        assert!(pool.iter(0).eq(&[11, 3, 2]));
+        assert_eq!(pool.list_of(handle), Ok(0));
+
+        // Reading does not resort
+        assert_eq!(*pool.find_mut(0, |v| *v == 2).unwrap(), 2);
+        assert!(pool.find_mut(0, |v| *v == 4).is_none());
+
+        *pool.find_mut(0, |v| *v == 2).unwrap() = 20;
+        assert!(pool.iter(0).eq(&[20, 11, 3]));
+
+        assert_eq!(pool.update(handle).unwrap().pop(), 11);
+        assert_eq!(pool.update(handle).map(|_| ()), Err(StaleHandle));
+        assert_eq!(pool.pop_back(0), Ok(3));
+        assert_eq!(pool.pop_back(1), Ok(4));
+        assert_eq!(pool.pop_back(1), Err(PopError));
+        assert!(pool.iter(0).eq(&[20]));
+    }
+
+    #[test]
+    fn test_pool_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut pool: LinkedListPool<Bomb, LinkedIndexU8, Max, 3, 2> = LinkedListPool::new();
+        pool.push(0, Bomb::new(1)).unwrap();
+        let bomb = pool.push(1, Bomb::new(0)).unwrap();
+
+        let r = catch_unwind(AssertUnwindSafe(|| pool.push(0, Bomb::new(0))));
+        assert!(r.is_err());
+        let r = catch_unwind(AssertUnwindSafe(|| pool.move_to(bomb, 0)));
+        assert!(r.is_err());
+
+        // The pool is unchanged and no nodes were lost
+        assert_eq!(pool.list_of(bomb), Ok(1));
+        assert_eq!((pool.len(0), pool.len(1)), (1, 1));
+        pool.push(0, Bomb::new(2)).unwrap();
+        assert!(pool.is_full());
+        assert!(pool.iter(0).map(|b| b.0).eq([2, 1]));
+
+        // A bomb that panics while resorting is dropped
+        let r = catch_unwind(AssertUnwindSafe(|| {
+            pool.find_mut(0, |b| b.0 == 2).unwrap().0 = 0;
+        }));
+        assert!(r.is_err());
+        assert_eq!(pool.len(0), 1);
+        assert!(!pool.is_full());
+    }
+
+    #[test]
+    fn test_pool_drop() {
+        use std::rc::Rc;
+
+        let value = Rc::new(());
+
+        {
+            let mut pool: LinkedListPool<Rc<()>, LinkedIndexU8, Max, 4, 2> = LinkedListPool::new();
+            pool.push(0, value.clone()).unwrap();
+            pool.push(1, value.clone()).unwrap();
+            pool.pop(1).unwrap();
+            assert_eq!(Rc::strong_count(&value), 2);
+        }
+
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+}
@@ -0,0 +1,178 @@
+//! A list shared through critical sections, enabled with the `critical-section` feature.
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+use super::{kind, Handle, LinkedList, LinkedListIndex, PopError, PushError};
+
+/// A [`LinkedList`] that can be shared between interrupts and thread mode, for example in a
+/// `static`.
+///
+/// Every operation runs in a critical section, so it is not interrupted by another operation on
+/// the same list. The closures of [`SharedLinkedList::with`] and
+/// [`SharedLinkedList::with_find_mut`] run inside the critical section too, so they should be
+/// short and must not use the same list again, which panics.
+///
+/// The shared list is [`Sync`] if the elements are [`Send`].
+///
+/// # Example
+///
+/// ```
+/// use linked_list::{LinkedIndexU8, LinkedList, Min, SharedLinkedList};
+///
+/// // Timer deadlines, shared with the timer interrupt
+/// static TIMERS: SharedLinkedList<u32, LinkedIndexU8, Min, 8> =
+///     SharedLinkedList::new(LinkedList::new_u8());
+///
+/// TIMERS.push(30).unwrap();
+/// TIMERS.push(10).unwrap();
+/// TIMERS.push(20).unwrap();
+///
+/// // Postpone the first timer
+/// TIMERS.with_find_mut(|t| *t == 10, |t| *t = 40);
+///
+/// assert_eq!(TIMERS.peek(), Some(20));
+/// assert_eq!(TIMERS.pop(), Ok(20));
+/// assert_eq!(TIMERS.with(|ll| ll.len()), 2);
+/// ```
+pub struct SharedLinkedList<T, Idx, Kind, const N: usize>
+where
+    Idx: LinkedListIndex,
+{
+    list: Mutex<RefCell<LinkedList<T, Idx, Kind, N>>>,
+}
+
+impl<T, Idx, Kind, const N: usize> SharedLinkedList<T, Idx, Kind, N>
+where
+    Idx: LinkedListIndex,
+{
+    /// Share a list, usually an empty one from [`LinkedList::new`] or one of the `new_*`
+    /// functions.
+    pub const fn new(list: LinkedList<T, Idx, Kind, N>) -> Self {
+        SharedLinkedList {
+            list: Mutex::new(RefCell::new(list)),
+        }
+    }
+
+    /// Get back the list.
+    pub fn into_inner(self) -> LinkedList<T, Idx, Kind, N> {
+        self.list.into_inner().into_inner()
+    }
+}
+
+impl<T, Idx, Kind, const N: usize> SharedLinkedList<T, Idx, Kind, N>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+{
+    /// Runs `f` with the list in a critical section.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` uses this list again.
+    pub fn with<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut LinkedList<T, Idx, Kind, N>) -> R,
+    {
+        critical_section::with(|cs| f(&mut self.list.borrow_ref_mut(cs)))
+    }
+
+    /// Pushes an element to the list and sorts it into place, see [`LinkedList::push`].
+    pub fn push(&self, value: T) -> Result<Handle<Idx>, PushError<T>> {
+        self.with(|ll| ll.push(value))
+    }
+
+    /// Pops the first element in the list, see [`LinkedList::pop`].
+    pub fn pop(&self) -> Result<T, PopError> {
+        self.with(|ll| ll.pop())
+    }
+
+    /// Get a copy of the first element in the list.
+    pub fn peek(&self) -> Option<T>
+    where
+        T: Copy,
+    {
+        self.with(|ll| ll.peek().copied())
+    }
+
+    /// Finds the first element for which `is` returns `true` and runs `f` with it, the element
+    /// is resorted in the same critical section. Returns `None` if no element was found.
+    ///
+    /// See [`LinkedList::find_mut`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `is` or `f` use this list again.
+    pub fn with_find_mut<R, P, F>(&self, is: P, f: F) -> Option<R>
+    where
+        P: FnMut(&T) -> bool,
+        F: FnOnce(&mut T) -> R,
+    {
+        self.with(|ll| {
+            let mut find = ll.find_mut(is)?;
+            Some(f(&mut find))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LinkedIndexU8, Max, StaleHandle};
+
+    static SHARED: SharedLinkedList<u32, LinkedIndexU8, Max, 4> =
+        SharedLinkedList::new(LinkedList::new_u8());
+
+    #[test]
+    fn test_shared() {
+        let handle = SHARED.push(1).unwrap();
+        SHARED.push(3).unwrap();
+        SHARED.push(2).unwrap();
+        assert_eq!(SHARED.peek(), Some(3));
+
+        // The element is resorted before the critical section ends
+        assert_eq!(SHARED.with_find_mut(|v| *v == 1, |v| *v = 4), Some(()));
+        assert_eq!(SHARED.peek(), Some(4));
+        assert_eq!(SHARED.with_find_mut(|v| *v == 1, |v| *v = 5), None);
+
+        assert_eq!(SHARED.with(|ll| ll.get(handle).copied()), Ok(4));
+        assert_eq!(SHARED.pop(), Ok(4));
+        assert_eq!(SHARED.with(|ll| ll.get(handle).copied()), Err(StaleHandle));
+        assert_eq!(SHARED.pop(), Ok(3));
+        assert_eq!(SHARED.pop(), Ok(2));
+        assert_eq!(SHARED.pop(), Err(PopError));
+        assert_eq!(SHARED.peek(), None);
+    }
+
+    #[test]
+    fn test_shared_threads() {
+        let shared: SharedLinkedList<u32, LinkedIndexU8, Max, 200> =
+            SharedLinkedList::new(LinkedList::new_u8());
+
+        std::thread::scope(|s| {
+            for t in 0..4 {
+                let shared = &shared;
+                s.spawn(move || {
+                    for v in 0..50 {
+                        shared.push(t * 50 + v).unwrap();
+                    }
+                });
+            }
+        });
+
+        let ll = shared.into_inner();
+        assert!(ll.is_full());
+        assert!(ll.iter().copied().eq((0..200).rev()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_reentrant() {
+        let shared: SharedLinkedList<u32, LinkedIndexU8, Max, 4> =
+            SharedLinkedList::new(LinkedList::new_u8());
+
+        shared.push(1).unwrap();
+        shared.with_find_mut(|_| true, |v| *v = shared.pop().unwrap());
+    }
+}
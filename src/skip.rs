@@ -0,0 +1,335 @@
+//! Express lanes over the sorted list, used by lists with a non-zero `LANES` like the
+//! `SkipLinkedList` of the `skip-list` feature.
+//!
+//! Every node takes part in a number of lanes decided by its index, where each lane holds about
+//! a quarter of the nodes of the lane below. A sorted search starts in the top lane and drops
+//! down a lane each time it would pass the searched value, which gives an expected `O(log N)`
+//! search when there are about `log4(N)` lanes.
+//!
+//! The lanes only link forward, so a node spends one index per lane. Unlinking a node instead
+//! walks back through the list to the previous node in each of its lanes, without comparing
+//! values, which keeps handles and `FindMut` working the same as without lanes. The walk is
+//! expected `O(1)` per lane averaged over the nodes, but up to `O(N)` for a node in the top lane.
+//!
+//! With `LANES` zero all of this compiles away, and the nodes have no lane links at all.
+
+use super::{LinkedListIndex, Links, Node};
+
+/// The number of lanes the node at `index` takes part in.
+#[inline]
+fn level<const LANES: usize>(index: usize) -> usize {
+    if LANES == 0 {
+        return 0;
+    }
+
+    // The finalizer of murmur3, spreads the indexes so every lane is a random subset
+    let mut hash = index as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^= hash >> 16;
+
+    (hash.trailing_zeros() as usize / 2).min(LANES)
+}
+
+/// The number of express lanes for a `SkipLinkedList` of capacity `n`.
+///
+/// Lanes are added while the top lane would still hold at least 4 nodes of a full list, so lists
+/// of fewer than 16 nodes get no lanes at all. Every lane costs one index in every node.
+///
+/// ```
+/// use linked_list::skip_lanes;
+///
+/// assert_eq!(skip_lanes(8), 0);
+/// assert_eq!(skip_lanes(100), 2);
+/// assert_eq!(skip_lanes(1000), 3);
+/// ```
+#[cfg(feature = "skip-list")]
+pub const fn skip_lanes(n: usize) -> usize {
+    let mut lanes = 0;
+    let mut top = n >> 4;
+
+    // The level of a node is at most 16, from the trailing zeros of a `u32` hash
+    while lanes < 16 && top > 0 {
+        lanes += 1;
+        top >>= 2;
+    }
+
+    lanes
+}
+
+/// Moves the lane positions found by `search_lanes` past the node at `new`, after it was linked
+/// at the end of the lanes.
+///
+/// # Safety
+///
+/// `new` must be a valid index for `Idx`.
+#[inline]
+pub(crate) unsafe fn advance_lanes<Idx, const LANES: usize>(prevs: &mut [Idx; LANES], new: usize)
+where
+    Idx: LinkedListIndex,
+{
+    for prev in prevs.iter_mut().take(level::<LANES>(new)) {
+        *prev = Idx::new_unchecked(new);
+    }
+}
+
+impl<Idx, const LANES: usize> Links<Idx, LANES>
+where
+    Idx: LinkedListIndex,
+{
+    /// Searches the lanes for the last node in each lane for which `before` returns `true`.
+    ///
+    /// `before` must return `true` for a prefix of the list, as it does for a sorted search.
+    ///
+    /// # Safety
+    ///
+    /// All nodes in the chain must be in `nodes` and hold a value.
+    pub(crate) unsafe fn search_lanes<T, F>(
+        &self,
+        nodes: &[Node<T, Idx, LANES>],
+        mut before: F,
+    ) -> [Idx; LANES]
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut prevs = [Idx::NONE; LANES];
+        let mut prev = Idx::NONE;
+
+        for lane in (0..LANES).rev() {
+            let mut next = match prev.option() {
+                Some(prev) => nodes.get_unchecked(prev).lanes[lane],
+                None => self.lanes[lane],
+            };
+
+            while let Some(current) = next.option() {
+                if !before(nodes.get_unchecked(current).value()) {
+                    break;
+                }
+
+                prev = next;
+                next = nodes.get_unchecked(current).lanes[lane];
+            }
+
+            prevs[lane] = prev;
+        }
+
+        prevs
+    }
+
+    /// Links the node at `new` into the lanes it takes part in, after the nodes found by
+    /// `search_lanes`.
+    ///
+    /// # Safety
+    ///
+    /// The node at `new` must not be linked into any lane.
+    pub(crate) unsafe fn link_lanes<T>(
+        &mut self,
+        nodes: &mut [Node<T, Idx, LANES>],
+        new: usize,
+        prevs: &[Idx; LANES],
+    ) {
+        for (lane, &prev) in prevs.iter().enumerate().take(level::<LANES>(new)) {
+            let next = match prev.option() {
+                Some(prev) => nodes.get_unchecked(prev).lanes[lane],
+                None => self.lanes[lane],
+            };
+
+            nodes.get_unchecked_mut(new).lanes[lane] = next;
+
+            match prev.option() {
+                Some(prev) => nodes.get_unchecked_mut(prev).lanes[lane] = Idx::new_unchecked(new),
+                None => self.lanes[lane] = Idx::new_unchecked(new),
+            }
+        }
+    }
+
+    /// Unlinks the node at `index` from the lanes it takes part in.
+    ///
+    /// The previous node in each lane is found by walking back through the list, so the node
+    /// must still be linked into the list.
+    ///
+    /// # Safety
+    ///
+    /// The node at `index` must be linked into the list and its lanes, and all nodes in the
+    /// chain must be in `nodes`.
+    pub(crate) unsafe fn unlink_lanes<T>(
+        &mut self,
+        nodes: &mut [Node<T, Idx, LANES>],
+        index: usize,
+    ) {
+        let levels = level::<LANES>(index);
+        let mut lane = 0;
+        let mut prev = nodes.get_unchecked(index).prev;
+
+        while lane < levels {
+            match prev.option() {
+                Some(p) => {
+                    // The closest previous node in a lane is in every lane below it too
+                    while lane < levels.min(level::<LANES>(p)) {
+                        let next = nodes.get_unchecked(index).lanes[lane];
+                        nodes.get_unchecked_mut(p).lanes[lane] = next;
+                        lane += 1;
+                    }
+
+                    prev = nodes.get_unchecked(p).prev;
+                }
+                None => {
+                    // The node is first in the remaining lanes
+                    for lane in lane..levels {
+                        self.lanes[lane] = nodes.get_unchecked(index).lanes[lane];
+                    }
+
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "skip-list"))]
+mod tests {
+    use super::*;
+    use crate::{LinkedIndexU16, LinkedIndexU8, Min, SkipLinkedList};
+
+    const N: usize = 1000;
+    const L: usize = skip_lanes(N);
+
+    type List = SkipLinkedList<u32, LinkedIndexU16, Min, N, L>;
+
+    #[test]
+    fn level_distribution() {
+        let in_lane = |lane| (0..4096).filter(|&i| level::<8>(i) > lane).count();
+
+        // Every lane has about a quarter of the nodes of the lane below
+        assert!((768..1280).contains(&in_lane(0)));
+        assert!((160..352).contains(&in_lane(1)));
+        assert!((32..96).contains(&in_lane(2)));
+
+        assert!((0..4096).all(|i| level::<2>(i) <= 2 && level::<0>(i) == 0));
+    }
+
+    #[test]
+    fn lane_count() {
+        assert_eq!(skip_lanes(0), 0);
+        assert_eq!(skip_lanes(15), 0);
+        assert_eq!(skip_lanes(16), 1);
+        assert_eq!(skip_lanes(63), 1);
+        assert_eq!(skip_lanes(64), 2);
+        assert_eq!(skip_lanes(1000), 3);
+        assert_eq!(skip_lanes(usize::MAX), 16);
+    }
+
+    #[test]
+    fn lanes_are_sorted_subsets() {
+        let mut ll: List = SkipLinkedList::new_u16();
+        let handles = fill(&mut ll);
+
+        // Remove and update some elements to exercise unlinking
+        for handle in handles.iter().step_by(3) {
+            ll.remove(*handle).unwrap();
+        }
+
+        for handle in handles.iter().skip(1).step_by(3) {
+            *ll.update(*handle).unwrap() += 250;
+        }
+
+        check_lanes(&ll);
+
+        // A clone links its lanes while appending the elements
+        check_lanes(&ll.clone());
+
+        // Removing from the ends unlinks the first and last nodes of the lanes
+        for _ in 0..100 {
+            ll.pop().unwrap();
+            ll.pop_back().unwrap();
+        }
+
+        check_lanes(&ll);
+    }
+
+    #[test]
+    fn find_mut_keeps_lanes() {
+        let mut ll: List = SkipLinkedList::new_u16();
+        fill(&mut ll);
+
+        for value in (0..500).step_by(7) {
+            if let Some(mut found) = ll.find_mut_ordered(&value) {
+                *found += 300;
+            }
+
+            if let Some(mut found) = ll.find_mut(|v| *v == value + 1) {
+                *found -= 1;
+            }
+        }
+
+        check_lanes(&ll);
+
+        let drained: Vec<_> = ll.drain().take(500).collect();
+        assert!(drained.windows(2).all(|w| w[0] <= w[1]));
+        check_lanes(&ll);
+    }
+
+    #[test]
+    fn no_lanes_for_small_lists() {
+        let mut ll: SkipLinkedList<u32, LinkedIndexU8, Min, 8, { skip_lanes(8) }> =
+            SkipLinkedList::new_u8();
+
+        for value in [3, 1, 2] {
+            ll.push(value).unwrap();
+        }
+
+        assert!(ll.iter().eq(&[1, 2, 3]));
+        assert_eq!(
+            core::mem::size_of::<Node<u32, LinkedIndexU8, { skip_lanes(8) }>>(),
+            core::mem::size_of::<Node<u32, LinkedIndexU8>>()
+        );
+    }
+
+    /// Pushes `N` pseudo random values into the list.
+    fn fill(ll: &mut List) -> Vec<crate::Handle<LinkedIndexU16>> {
+        let mut seed = 1u32;
+
+        (0..N)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                ll.push(seed % 500).unwrap()
+            })
+            .collect()
+    }
+
+    /// Checks that every lane is a sorted subset of the list, holding every node in the list
+    /// that takes part in it.
+    fn check_lanes(ll: &List) {
+        let base: Vec<_> = ll.iter().copied().collect();
+        assert!(base.windows(2).all(|w| w[0] <= w[1]));
+
+        for lane in 0..L {
+            let mut in_lane = Vec::new();
+            let mut index = ll.links.lanes[lane];
+
+            while let Some(i) = index.option() {
+                assert!(level::<L>(i) > lane);
+
+                in_lane.push(i);
+                index = ll.node_at(i).lanes[lane];
+            }
+
+            let mut expected = Vec::new();
+            let mut index = ll.links.head;
+
+            while let Some(i) = index.option() {
+                if level::<L>(i) > lane {
+                    expected.push(i);
+                }
+
+                index = ll.node_at(i).next;
+            }
+
+            assert_eq!(in_lane, expected);
+        }
+    }
+}
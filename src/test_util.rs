@@ -0,0 +1,52 @@
+//! Elements shared by the tests of the list types.
+
+use core::cmp::Ordering;
+use std::rc::Rc;
+
+/// Only compares the priority, so ties can be told apart by the tag. Tests check the tags
+/// explicitly, as elements with equal priorities are equal.
+#[derive(Debug, Clone)]
+pub(crate) struct Prio(pub(crate) u32, pub(crate) char);
+
+impl PartialEq for Prio {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl PartialOrd for Prio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+/// Panics when an element with priority 0 is compared, counts live elements with the `Rc`.
+#[derive(Debug)]
+pub(crate) struct Bomb(pub(crate) u32, pub(crate) Rc<()>);
+
+impl Bomb {
+    /// An element that is not counted.
+    pub(crate) fn new(prio: u32) -> Self {
+        Bomb(prio, Rc::new(()))
+    }
+}
+
+impl PartialEq for Bomb {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl PartialOrd for Bomb {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        assert!(self.0 != 0 && other.0 != 0, "compared a bomb");
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl Clone for Bomb {
+    fn clone(&self) -> Self {
+        assert!(self.0 != 13, "cloned a bomb");
+        Bomb(self.0, self.1.clone())
+    }
+}
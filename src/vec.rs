@@ -0,0 +1,297 @@
+//! A list with the nodes on the heap, enabled with the `alloc` feature.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use super::{
+    kind, storage::Storage, FreeList, LinkedListIndex, LinkedListInner, Links, Node, Overflow,
+};
+
+/// A linked list with the nodes in a [`Vec`], which grows when an element is pushed to a full
+/// list.
+///
+/// It has the same API as a [`LinkedListView`](crate::LinkedListView), except that pushing only
+/// fails once the list has as many elements as the index type can address. The nodes are added
+/// one at a time and released nodes are reused before the list grows, so the capacity is the
+/// largest number of elements the list has held. For the same reason the list is only
+/// [full](LinkedListInner::is_full) once it can not grow any further.
+///
+/// # Example
+///
+/// ```
+/// use linked_list::{LinkedIndexU8, LinkedListVec, Min};
+/// let mut ll: LinkedListVec<u32, LinkedIndexU8, Min> = LinkedListVec::new();
+///
+/// for v in (0..300).rev() {
+///     // The index type limits the list to 255 elements
+///     assert_eq!(ll.push(v).is_ok(), v >= 45);
+/// }
+///
+/// assert_eq!(ll.len(), 255);
+/// assert_eq!(ll.peek(), Some(&45));
+/// ```
+pub type LinkedListVec<T, Idx, Kind> = LinkedListInner<T, Idx, Kind, Vec<Node<T, Idx>>>;
+
+unsafe impl<T, Idx, const LANES: usize> Storage<T, Idx, LANES> for Vec<Node<T, Idx, LANES>>
+where
+    Idx: LinkedListIndex,
+{
+    #[inline(always)]
+    fn nodes(&self) -> &[Node<T, Idx, LANES>] {
+        self
+    }
+
+    #[inline(always)]
+    fn nodes_mut(&mut self) -> &mut [Node<T, Idx, LANES>] {
+        self
+    }
+
+    #[inline]
+    fn can_grow(&self) -> bool {
+        self.len() < Idx::MAX_LEN
+    }
+
+    #[inline]
+    fn grow(&mut self) -> bool {
+        if self.can_grow() {
+            self.push(Node::UNINIT);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<T, Idx, Kind> LinkedListVec<T, Idx, Kind>
+where
+    Idx: LinkedListIndex,
+{
+    /// Create a new empty list, which does not allocate until an element is pushed.
+    pub const fn new() -> Self {
+        LinkedListInner {
+            links: Links::EMPTY,
+            free: FreeList::EMPTY,
+            _marker: PhantomData,
+            list: Vec::new(),
+        }
+    }
+
+    /// Create a new empty list with room for `capacity` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is larger than the maximum value of the index type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedIndexU16, LinkedListVec, Max};
+    /// let ll: LinkedListVec<u32, LinkedIndexU16, Max> = LinkedListVec::with_capacity(100);
+    ///
+    /// assert_eq!(ll.capacity(), 100);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(
+            capacity <= Idx::MAX_LEN,
+            "`capacity` is too large for the index type"
+        );
+
+        let mut list = Self::new();
+        list.list.resize_with(capacity, || Node::UNINIT);
+        list
+    }
+}
+
+impl<T, Idx, Kind> LinkedListVec<T, Idx, Kind>
+where
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+{
+    /// Create a new list from the elements of an iterator.
+    ///
+    /// If an element can not be pushed, the list is returned together with the overflow: the
+    /// rejected element followed by the rest of the iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use linked_list::{LinkedIndexU8, LinkedListVec, Max};
+    /// type List = LinkedListVec<u32, LinkedIndexU8, Max>;
+    ///
+    /// let ll = List::try_from_iter(0..10).unwrap();
+    /// assert_eq!(ll.peek(), Some(&9));
+    ///
+    /// // The index type limits the list to 255 elements
+    /// let (ll, overflow) = List::try_from_iter(0..300).unwrap_err();
+    /// assert_eq!(ll.len(), 255);
+    /// assert!(overflow.eq(255..300));
+    /// ```
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, (Self, Overflow<T, I::IntoIter>)>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut list = Self::new();
+
+        match list.push_iter(iter.into_iter()) {
+            Ok(()) => Ok(list),
+            Err(overflow) => Err((list, overflow)),
+        }
+    }
+}
+
+impl<T, Idx, Kind> Default for LinkedListVec<T, Idx, Kind>
+where
+    Idx: LinkedListIndex,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Clones the list with the elements in sorted order in the first nodes, and with the capacity
+/// set to the number of elements.
+///
+/// Handles to the original list do not point to the same elements in the clone.
+impl<T, Idx, Kind> Clone for LinkedListVec<T, Idx, Kind>
+where
+    T: Clone,
+    Idx: LinkedListIndex,
+    Kind: kind::Kind<T>,
+{
+    fn clone(&self) -> Self {
+        let mut list = Self::with_capacity(self.len());
+        // Safety: The new list is empty and has room for all elements.
+        unsafe { self.clone_compact_into(&mut list) };
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        LinkedIndexU16, LinkedIndexU8, LinkedList, Max, Min, MinStable, PushError, StaleHandle,
+    };
+
+    #[test]
+    fn test_grow() {
+        let mut ll: LinkedListVec<u32, LinkedIndexU16, Max> = LinkedListVec::new();
+        assert_eq!(ll.capacity(), 0);
+        assert!(ll.is_empty());
+
+        let handles: Vec<_> = (0..1000).map(|v| ll.push(v * 7 % 1000).unwrap()).collect();
+        assert_eq!(ll.len(), 1000);
+        assert_eq!(ll.capacity(), 1000);
+        assert!(ll.iter().copied().eq((0..1000).rev()));
+
+        // Released nodes are reused before the list grows
+        for handle in &handles[..500] {
+            ll.remove(*handle).unwrap();
+        }
+        for v in 0..500 {
+            ll.push(v).unwrap();
+        }
+        assert_eq!(ll.capacity(), 1000);
+        assert_eq!(ll.remove(handles[0]), Err(StaleHandle));
+
+        // Handles stay valid while the list grows
+        let handle = handles[999];
+        let value = *ll.get(handle).unwrap();
+        for v in 0..1000 {
+            ll.push(v).unwrap();
+        }
+        assert_eq!(ll.get(handle), Ok(&value));
+        assert_eq!(ll.capacity(), 2000);
+    }
+
+    #[test]
+    fn test_is_full() {
+        let mut ll: LinkedListVec<u32, LinkedIndexU8, Min> = LinkedListVec::with_capacity(2);
+        ll.extend([1, 2]);
+
+        // A list that can grow is not full, also when all nodes are taken
+        assert!(!ll.is_full());
+        unsafe { ll.push_unchecked(3) };
+        assert_eq!(ll.capacity(), 3);
+
+        ll.extend(4..=255);
+        assert!(ll.is_full());
+        assert_eq!(ll.push(0), Err(PushError::Full(0)));
+        assert_eq!(ll.push_evict(0), Ok(Some(255)));
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let ll = LinkedListVec::<_, LinkedIndexU16, Max>::try_from_iter([2, 5, 1, 4]).unwrap();
+
+        let mut iter = ll.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some(1));
+        assert!(iter.eq([5, 4, 2]));
+    }
+
+    #[test]
+    fn test_find_mut() {
+        let mut ll: LinkedListVec<(u32, char), LinkedIndexU16, MinStable> =
+            LinkedListVec::with_capacity(2);
+
+        ll.push((2, 'a')).unwrap();
+        ll.push((1, 'b')).unwrap();
+        ll.push((3, 'c')).unwrap();
+
+        let mut find = ll.find_mut(|v| v.1 == 'b').unwrap();
+        find.0 = 4;
+        find.finish();
+        assert_eq!(format!("{:?}", ll), "[(2, 'a'), (3, 'c'), (4, 'b')]");
+
+        assert_eq!(ll.peek_mut().unwrap().pop(), (2, 'a'));
+        assert!(ll.drain().eq([(3, 'c'), (4, 'b')]));
+        assert!(ll.is_empty());
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut ll: LinkedListVec<u32, LinkedIndexU16, Max> = LinkedListVec::new();
+        ll.extend([5, 1, 4, 2, 3]);
+        ll.pop().unwrap();
+
+        let clone = ll.clone();
+        assert_eq!(clone.capacity(), 4);
+        assert_eq!(clone, ll);
+        assert!(clone.iter().eq(&[4, 3, 2, 1]));
+
+        // Lists are equal to lists with other storage holding the same elements
+        let mut array: LinkedList<u32, LinkedIndexU16, Max, 8> = LinkedList::new();
+        array.extend([1, 2, 3, 4]);
+        assert_eq!(ll, array);
+        assert_eq!(*array.as_view(), ll);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+
+        assert_send::<LinkedListVec<u32, LinkedIndexU16, Max>>();
+        assert_sync::<LinkedListVec<u32, LinkedIndexU16, Max>>();
+        assert_send::<LinkedListVec<core::cell::Cell<u32>, LinkedIndexU16, Max>>();
+    }
+
+    #[test]
+    fn test_vec_drop() {
+        use std::rc::Rc;
+
+        let value = Rc::new(0);
+
+        {
+            let mut ll: LinkedListVec<Rc<u32>, LinkedIndexU16, Max> = LinkedListVec::new();
+            for _ in 0..10 {
+                ll.push(value.clone()).unwrap();
+            }
+            ll.pop().unwrap();
+            assert_eq!(Rc::strong_count(&value), 10);
+        }
+
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+}